@@ -1,5 +1,5 @@
 use anyhow::Result;
-use chromatica::Chromatica;
+use chromatica::{Chromatica, ResourceKind};
 use std::path::PathBuf;
 use tokio::time::Duration;
 
@@ -36,8 +36,7 @@ async fn main() -> Result<()> {
     let (mut request_subscriber, mut response_subscriber) = page.subscribe_to_requests().await?;
     let request_task = tokio::spawn(async move {
         while let Some(request) = request_subscriber.next().await {
-            //https://chromedevtools.github.io/devtools-protocol/tot/Network/#type-ResourceType
-            if request.resource_type() == "Image" {
+            if request.resource_kind() == ResourceKind::Image {
                 let _ = request.abort().await;
             } else {
                 let _ = request.continue_request().await;
@@ -47,8 +46,7 @@ async fn main() -> Result<()> {
 
     let response_task = tokio::spawn(async move {
         while let Some(response) = response_subscriber.next().await {
-            //https://chromedevtools.github.io/devtools-protocol/tot/Network/#type-ResourceType
-            if response.resource_type() == "Image" {
+            if response.resource_kind() == ResourceKind::Image {
                 let _ = response.abort().await;
             } else {
                 let _ = response.continue_response().await;
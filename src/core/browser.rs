@@ -1,9 +1,13 @@
 use anyhow::Result;
+use serde_json::Value;
 use std::sync::Arc;
+use tokio::sync::broadcast;
 
 use super::browser_context::BrowserContext;
 use super::cdp::browser::Browser as CdpBrowser;
+use super::cdp::target_manager::TargetEvent;
 use super::page::Page;
+use super::worker::Worker;
 
 #[derive(Debug, Clone)]
 pub enum Browser {
@@ -21,6 +25,7 @@ impl Browser {
         }
     }
 
+    ///The context lives as long as the browser, until it's closed with `BrowserContext::close`.
     pub async fn new_browser_context(
         &self,
         proxy: Option<&str>,
@@ -36,6 +41,63 @@ impl Browser {
         }
     }
 
+    ///Isolated context without proxy settings. It's disposed together with its pages once the last
+    ///handle to it and to the pages from its `new_page` is dropped, or right away with `close`.
+    ///Pages reached otherwise, e.g. popups or `pages`, don't keep it alive.
+    pub async fn new_incognito_context(&self) -> Result<Arc<BrowserContext>> {
+        match self {
+            Self::CDP(browser) => {
+                let browser_context = browser.new_incognito_context().await?;
+                Ok(Arc::new(BrowserContext::CDP(browser_context)))
+            }
+        }
+    }
+
+    ///Created, destroyed and crashed targets of the whole browser, including workers.
+    pub fn subscribe_to_targets(&self) -> Result<broadcast::Receiver<TargetEvent>> {
+        match self {
+            Self::CDP(browser) => browser.subscribe_to_targets(),
+            // Self::BiDi(browser) => browser.subscribe_to_targets(),
+        }
+    }
+
+    ///Page of a top level target, e.g. from `TargetEvent::Created` for a tab opened by the site.
+    pub async fn page(&self, target_id: &str) -> Option<Page> {
+        match self {
+            Self::CDP(browser) => {
+                let page = browser.page(&target_id.to_string()).await?;
+                Some(Page::CDP(page))
+            }
+        }
+    }
+
+    ///Dedicated, shared and service workers currently running in the browser.
+    pub async fn workers(&self) -> Vec<Worker> {
+        match self {
+            Self::CDP(browser) => browser
+                .workers()
+                .await
+                .into_iter()
+                .map(Worker::CDP)
+                .collect(),
+        }
+    }
+
+    pub async fn send_cdp(&self, method: &str, params: Value) -> Result<Value> {
+        match self {
+            Self::CDP(browser) => browser.send_cdp(method, params).await,
+        }
+    }
+
+    ///When enabled, dropping the last clone of the browser closes the pages it opened and disconnects.
+    ///Cleanup runs on a spawned task, so it's best effort; awaiting `disconnect` does the same deterministically.
+    pub fn close_on_drop(&self, close: bool) {
+        match self {
+            Self::CDP(browser) => browser.close_on_drop(close),
+            // Self::BiDi(browser) => browser.close_on_drop(close),
+        }
+    }
+
     pub async fn disconnect(self) {
         match self {
             Self::CDP(browser) => {
@@ -21,6 +21,8 @@ impl BrowserContext {
         }
     }
 
+    ///Closes the context's pages and disposes it. For an incognito context, dropping the last handle
+    ///of the context and of the pages from `new_page` does the same in the background.
     pub async fn close(&self) -> Result<()> {
         match self {
             Self::CDP(browser_context) => browser_context.close().await,
@@ -1,19 +1,55 @@
-use anyhow::Result;
+use anyhow::{Result, anyhow};
+use serde_json::Value;
 use std::sync::Arc;
+use tokio::sync::broadcast;
 
 use super::browser_context::BrowserContext;
 use super::connection::Connection;
+use super::domains::target::TargetId;
 use super::page::Page;
-use super::target_manager::TargetManager;
+use super::target_manager::{TargetEvent, TargetManager};
+use super::worker::Worker;
+
+//Shared by all clones of a browser, dropped with the last one.
+#[derive(Debug)]
+struct BrowserHandle {
+    connection: Arc<Connection>,
+}
+
+impl Drop for BrowserHandle {
+    fn drop(&mut self) {
+        if !self.connection.close_pages_on_disconnect() {
+            return;
+        }
+        let connection = self.connection.clone();
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            handle.spawn(async move {
+                connection.disconnect().await;
+            });
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct Browser {
     connection: Arc<Connection>,
+    _handle: Arc<BrowserHandle>,
 }
 
 impl Browser {
     pub fn new(connection: Arc<Connection>) -> Self {
-        Self { connection }
+        let handle = Arc::new(BrowserHandle {
+            connection: connection.clone(),
+        });
+        Self {
+            connection,
+            _handle: handle,
+        }
+    }
+
+    ///Closes pages opened by this browser and disconnects once the last handle is dropped.
+    pub fn close_on_drop(&self, close: bool) {
+        self.connection.set_close_pages_on_disconnect(close);
     }
 
     fn target_manager(&self) -> Option<&Arc<TargetManager>> {
@@ -41,6 +77,41 @@ impl Browser {
         Ok(browser_context)
     }
 
+    pub async fn new_incognito_context(&self) -> Result<Arc<BrowserContext>> {
+        let target_manager = self.target_manager().unwrap();
+        target_manager.create_incognito_context().await
+    }
+
+    pub fn subscribe_to_targets(&self) -> Result<broadcast::Receiver<TargetEvent>> {
+        match self.target_manager() {
+            Some(target_manager) => Ok(target_manager.subscribe_to_targets()),
+            None => Err(anyhow!("Target manager is not available")),
+        }
+    }
+
+    pub async fn page(&self, target_id: &TargetId) -> Option<Page> {
+        let target_manager = self.target_manager()?;
+        target_manager.get_page(target_id).await
+    }
+
+    pub async fn workers(&self) -> Vec<Worker> {
+        match self.target_manager() {
+            Some(target_manager) => target_manager
+                .get_workers()
+                .await
+                .iter()
+                .map(|worker| worker.as_ref().clone())
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    ///Sends a raw CDP command on the browser session and returns the raw result.
+    pub async fn send_cdp(&self, method: &str, params: Value) -> Result<Value> {
+        let response = self.connection.send(method, &params, None).await?;
+        response.result_as::<Value>()
+    }
+
     pub async fn disconnect(self) {
         self.connection.disconnect().await;
     }
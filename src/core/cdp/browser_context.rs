@@ -1,4 +1,7 @@
-use std::sync::{Arc, Weak};
+use std::sync::{
+    Arc, Weak,
+    atomic::{AtomicBool, Ordering},
+};
 
 use super::connection::Connection;
 use super::domains::browser::BrowserContextID;
@@ -7,15 +10,74 @@ use super::target_manager::TargetManager;
 
 use anyhow::{Result, anyhow};
 
+//Shared by all clones of a context and the pages it created, an incognito context is disposed
+//with the last of them unless it was closed already.
+#[derive(Debug)]
+pub(crate) struct BrowserContextHandle {
+    id: BrowserContextID,
+    connection: Weak<Connection>,
+    dispose_on_drop: bool,
+    closed: AtomicBool,
+}
+
+impl Drop for BrowserContextHandle {
+    fn drop(&mut self) {
+        if !self.dispose_on_drop || self.closed.load(Ordering::SeqCst) {
+            return;
+        }
+        let Some(target_manager) = self
+            .connection
+            .upgrade()
+            .and_then(|conn| conn.target_manager().cloned())
+        else {
+            return;
+        };
+        let id = self.id.clone();
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            handle.spawn(async move {
+                let _ = target_manager.close_browser_context(&id).await;
+            });
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct BrowserContext {
     id: BrowserContextID,
     connection: Weak<Connection>,
+    handle: Arc<BrowserContextHandle>,
 }
 
 impl BrowserContext {
     pub fn new(id: BrowserContextID, connection: Weak<Connection>) -> Self {
-        Self { id, connection }
+        Self::with_handle(id, connection, false)
+    }
+
+    ///Context disposed together with its pages once the last handle to it and to the pages from `new_page` is dropped.
+    pub fn new_incognito(id: BrowserContextID, connection: Weak<Connection>) -> Self {
+        Self::with_handle(id, connection, true)
+    }
+
+    fn with_handle(
+        id: BrowserContextID,
+        connection: Weak<Connection>,
+        dispose_on_drop: bool,
+    ) -> Self {
+        let handle = Arc::new(BrowserContextHandle {
+            id: id.clone(),
+            connection: connection.clone(),
+            dispose_on_drop,
+            closed: AtomicBool::new(false),
+        });
+        Self {
+            id,
+            connection,
+            handle,
+        }
+    }
+
+    pub fn id(&self) -> &BrowserContextID {
+        &self.id
     }
 
     fn connection(&self) -> Option<Arc<Connection>> {
@@ -41,14 +103,16 @@ impl BrowserContext {
             return Err(anyhow!("Target manager is not available"));
         };
         let page = target_manager.create_page(Some(&self.id)).await?;
-        Ok(page)
+        Ok(page.with_context_handle(self.handle.clone()))
     }
 
+    ///Closes the pages of the context, then disposes it.
     pub async fn close(&self) -> Result<()> {
         let Some(target_manager) = self.target_manager() else {
             return Err(anyhow!("Target manager is not available"));
         };
         target_manager.close_browser_context(&self.id).await?;
+        self.handle.closed.store(true, Ordering::SeqCst);
         Ok(())
     }
 }
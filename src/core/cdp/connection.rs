@@ -6,10 +6,12 @@
 // };
 use super::domains::dom_storage::DomStorageItemAdded;
 use super::domains::fetch::{AuthRequired, RequestPaused};
-use super::domains::network::LoadingFailed;
+use super::domains::network::{LoadingFailed, LoadingFinished, RequestWillBeSent};
 use super::domains::page::{
-    FileChooserOpened, FrameAttached, FrameDetached, JavascriptDialogOpening, LifecycleEvent,
+    FileChooserOpened, FrameAttached, FrameDetached, FrameNavigated, JavascriptDialogOpening,
+    LifecycleEvent,
 };
+use super::domains::runtime::{ConsoleAPICalled, ExceptionThrown};
 use super::domains::target::{SessionId, TargetCrashed, TargetCreated, TargetDestroyed};
 
 use super::target_manager::TargetManager;
@@ -23,12 +25,240 @@ use serde_json::Value;
 // use std::collections::HashSet;
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::{Arc, Weak};
-use tokio::sync::{RwLock, mpsc, oneshot};
+use tokio::sync::{RwLock, broadcast, mpsc, oneshot};
 use tokio::task::JoinHandle;
-use tokio_tungstenite::connect_async;
+use tokio::time::Duration;
+use tokio_tungstenite::connect_async_with_config;
 use tokio_tungstenite::tungstenite::Message as WsMessage;
+use tokio_tungstenite::tungstenite::protocol::WebSocketConfig;
 use tokio_tungstenite::tungstenite::protocol::frame::Utf8Bytes;
 
+//Screenshots and PDFs come back as single base64 message, tungstenite defaults (64MB/16MB) are too small for long pages.
+const DEFAULT_MAX_MESSAGE_SIZE: usize = 512 << 20;
+const DEFAULT_MAX_FRAME_SIZE: usize = 256 << 20;
+
+const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+const DEFAULT_PAGE_TIMEOUT: Duration = Duration::from_secs(30);
+//How long disconnect lets the dispatcher hand out events already read before aborting it
+const DISPATCHER_FLUSH_TIMEOUT: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProtocolDirection {
+    Sent,
+    Received,
+}
+
+///Hook getting every raw protocol message, e.g. to debug what the library actually sends.
+type LogFn = dyn Fn(ProtocolDirection, &str) + Send + Sync;
+
+#[derive(Clone)]
+pub struct ProtocolLogger(Arc<LogFn>);
+
+impl ProtocolLogger {
+    pub fn new<F>(logger: F) -> Self
+    where
+        F: Fn(ProtocolDirection, &str) + Send + Sync + 'static,
+    {
+        Self(Arc::new(logger))
+    }
+
+    fn log(&self, direction: ProtocolDirection, message: &str) {
+        (self.0)(direction, message)
+    }
+}
+
+impl std::fmt::Debug for ProtocolLogger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ProtocolLogger")
+    }
+}
+
+///How events are buffered for user-facing streams, e.g. console messages and page errors, until they're read.
+///Internal subscriptions (targets, interception, dialogs, navigation waits) are always unbounded,
+///losing or stalling their events would hang pages.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EventChannelMode {
+    #[default]
+    Unbounded,
+    ///Keeps the last `capacity` events, a stream that falls behind silently loses the oldest ones.
+    DropOldest(usize),
+    ///Dispatching waits until the stream has room, so an unread stream delays events for everyone.
+    Backpressure(usize),
+}
+
+///What happens when the WebSocket to the browser closes. Only `Never` is supported for now,
+///pending commands fail and pages stop working until the browser is connected again.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReconnectPolicy {
+    #[default]
+    Never,
+}
+
+impl EventChannelMode {
+    fn channel(&self) -> (EventSender, EventReceiver) {
+        match *self {
+            Self::Unbounded => {
+                let (tx, rx) = mpsc::unbounded_channel();
+                (EventSender::Unbounded(tx), EventReceiver::Unbounded(rx))
+            }
+            Self::DropOldest(capacity) => {
+                let (tx, rx) = broadcast::channel(capacity.max(1));
+                (EventSender::DropOldest(tx), EventReceiver::DropOldest(rx))
+            }
+            Self::Backpressure(capacity) => {
+                let (tx, rx) = mpsc::channel(capacity.max(1));
+                (
+                    EventSender::Backpressure(tx),
+                    EventReceiver::Backpressure(rx),
+                )
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+enum EventSender {
+    Unbounded(mpsc::UnboundedSender<Arc<Event>>),
+    DropOldest(broadcast::Sender<Arc<Event>>),
+    Backpressure(mpsc::Sender<Arc<Event>>),
+}
+
+impl EventSender {
+    //Fails only when the receiver is gone.
+    async fn send(&self, event: Arc<Event>) -> Result<()> {
+        let sent = match self {
+            Self::Unbounded(tx) => tx.send(event).is_ok(),
+            Self::DropOldest(tx) => tx.send(event).is_ok(),
+            Self::Backpressure(tx) => tx.send(event).await.is_ok(),
+        };
+        if sent {
+            Ok(())
+        } else {
+            Err(anyhow!("Event receiver is dropped"))
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum EventReceiver {
+    Unbounded(mpsc::UnboundedReceiver<Arc<Event>>),
+    DropOldest(broadcast::Receiver<Arc<Event>>),
+    Backpressure(mpsc::Receiver<Arc<Event>>),
+}
+
+impl EventReceiver {
+    ///`None` once the subscriber is removed from the connection.
+    pub async fn recv(&mut self) -> Option<Arc<Event>> {
+        match self {
+            Self::Unbounded(rx) => rx.recv().await,
+            Self::Backpressure(rx) => rx.recv().await,
+            Self::DropOldest(rx) => loop {
+                match rx.recv().await {
+                    Ok(event) => return Some(event),
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            },
+        }
+    }
+
+    pub fn try_recv(&mut self) -> Result<Arc<Event>> {
+        let event = match self {
+            Self::Unbounded(rx) => rx.try_recv().ok(),
+            Self::Backpressure(rx) => rx.try_recv().ok(),
+            Self::DropOldest(rx) => loop {
+                match rx.try_recv() {
+                    Ok(event) => break Some(event),
+                    Err(broadcast::error::TryRecvError::Lagged(_)) => continue,
+                    Err(_) => break None,
+                }
+            },
+        };
+        event.ok_or_else(|| anyhow!("No event received"))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ConnectionOptions {
+    pub max_message_size: Option<usize>,
+    pub max_frame_size: Option<usize>,
+    pub request_timeout: Duration,
+    pub default_timeout: Duration,
+    pub keepalive_interval: Option<Duration>,
+    pub protocol_logger: Option<ProtocolLogger>,
+    pub event_channel_mode: EventChannelMode,
+    pub reconnect_policy: ReconnectPolicy,
+}
+
+impl ConnectionOptions {
+    pub fn default() -> Self {
+        Self {
+            max_message_size: Some(DEFAULT_MAX_MESSAGE_SIZE),
+            max_frame_size: Some(DEFAULT_MAX_FRAME_SIZE),
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+            default_timeout: DEFAULT_PAGE_TIMEOUT,
+            keepalive_interval: None,
+            protocol_logger: None,
+            event_channel_mode: EventChannelMode::Unbounded,
+            reconnect_policy: ReconnectPolicy::Never,
+        }
+    }
+
+    ///`None` removes the limit.
+    pub fn max_message_size(mut self, max_message_size: Option<usize>) -> Self {
+        self.max_message_size = max_message_size;
+        self
+    }
+
+    pub fn max_frame_size(mut self, max_frame_size: Option<usize>) -> Self {
+        self.max_frame_size = max_frame_size;
+        self
+    }
+
+    ///How long a single CDP command may wait for its response.
+    pub fn request_timeout(mut self, request_timeout: Duration) -> Self {
+        self.request_timeout = request_timeout;
+        self
+    }
+
+    ///Initial `default_timeout` of new pages and frames.
+    pub fn default_timeout(mut self, default_timeout: Duration) -> Self {
+        self.default_timeout = default_timeout;
+        self
+    }
+
+    ///Sends WebSocket pings, so proxies and load balancers don't drop idle connections to remote browsers.
+    pub fn keepalive_interval(mut self, keepalive_interval: Option<Duration>) -> Self {
+        self.keepalive_interval = keepalive_interval;
+        self
+    }
+
+    pub fn protocol_logger(mut self, protocol_logger: Option<ProtocolLogger>) -> Self {
+        self.protocol_logger = protocol_logger;
+        self
+    }
+
+    pub fn event_channel_mode(mut self, event_channel_mode: EventChannelMode) -> Self {
+        self.event_channel_mode = event_channel_mode;
+        self
+    }
+
+    pub fn reconnect_policy(mut self, reconnect_policy: ReconnectPolicy) -> Self {
+        self.reconnect_policy = reconnect_policy;
+        self
+    }
+
+    pub fn build(self) -> Self {
+        self
+    }
+
+    fn ws_config(&self) -> WebSocketConfig {
+        WebSocketConfig::default()
+            .max_message_size(self.max_message_size)
+            .max_frame_size(self.max_frame_size)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Response {
     pub id: usize,
@@ -41,14 +271,45 @@ pub struct Response {
 }
 
 impl Response {
+    ///Protocol errors come back as `CdpError`, get the code with `error.downcast_ref::<CdpError>()`.
     pub fn result_as<T: for<'de> Deserialize<'de>>(self) -> Result<T> {
-        match self.result {
-            Some(value) => Ok(serde_json::from_value(value)?),
-            None => Err(anyhow!(self.error.unwrap().message)),
+        match (self.result, self.error) {
+            (Some(value), _) => Ok(serde_json::from_value(value)?),
+            (None, Some(error)) => Err(CdpError {
+                code: error.code,
+                message: error.message,
+            }
+            .into()),
+            (None, None) => Err(anyhow!(
+                "Malformed response to command {}: neither result nor error",
+                self.id
+            )),
         }
     }
 }
 
+///Error returned by the browser for a command. Displays as the browser's message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CdpError {
+    pub code: i32,
+    pub message: String,
+}
+
+impl CdpError {
+    ///Generic failure, e.g. a node that no longer exists.
+    pub const SERVER_ERROR: i32 = -32000;
+    pub const METHOD_NOT_FOUND: i32 = -32601;
+    pub const INVALID_PARAMS: i32 = -32602;
+}
+
+impl std::fmt::Display for CdpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for CdpError {}
+
 #[derive(Debug, Clone, Deserialize)]
 pub enum EventParams {
     TargetCreated(TargetCreated),
@@ -56,12 +317,17 @@ pub enum EventParams {
     TargetCrashed(TargetCrashed),
     FrameAttached(FrameAttached),
     FrameDetached(FrameDetached),
+    FrameNavigated(FrameNavigated),
     LifecycleEvent(LifecycleEvent),
     JavascriptDialogOpening(JavascriptDialogOpening),
     FileChooserOpened(FileChooserOpened),
     RequestPaused(RequestPaused),
     AuthRequired(AuthRequired),
     LoadingFailed(LoadingFailed),
+    LoadingFinished(LoadingFinished),
+    RequestWillBeSent(RequestWillBeSent),
+    ConsoleAPICalled(ConsoleAPICalled),
+    ExceptionThrown(ExceptionThrown),
     Value(Value),
 }
 
@@ -107,6 +373,9 @@ impl<'de> Deserialize<'de> for Event {
             "Page.frameDetached" => EventParams::FrameDetached(
                 serde_json::from_value(params).map_err(serde::de::Error::custom)?,
             ),
+            "Page.frameNavigated" => EventParams::FrameNavigated(
+                serde_json::from_value(params).map_err(serde::de::Error::custom)?,
+            ),
             "Page.lifecycleEvent" => EventParams::LifecycleEvent(
                 serde_json::from_value(params).map_err(serde::de::Error::custom)?,
             ),
@@ -125,6 +394,18 @@ impl<'de> Deserialize<'de> for Event {
             "Network.loadingFailed" => EventParams::LoadingFailed(
                 serde_json::from_value(params).map_err(serde::de::Error::custom)?,
             ),
+            "Network.loadingFinished" => EventParams::LoadingFinished(
+                serde_json::from_value(params).map_err(serde::de::Error::custom)?,
+            ),
+            "Network.requestWillBeSent" => EventParams::RequestWillBeSent(
+                serde_json::from_value(params).map_err(serde::de::Error::custom)?,
+            ),
+            "Runtime.consoleAPICalled" => EventParams::ConsoleAPICalled(
+                serde_json::from_value(params).map_err(serde::de::Error::custom)?,
+            ),
+            "Runtime.exceptionThrown" => EventParams::ExceptionThrown(
+                serde_json::from_value(params).map_err(serde::de::Error::custom)?,
+            ),
             "DOM.attributeModified" => EventParams::Value(params),
             "DOM.attributeRemoved" => EventParams::Value(params),
             "DOM.characterDataModified" => EventParams::Value(params),
@@ -177,14 +458,14 @@ pub enum IncomingMessage {
 pub struct EventSubscriber {
     methods: DashSet<String>,
     session_ids: DashSet<Arc<SessionId>>,
-    tx: mpsc::UnboundedSender<Arc<Event>>,
+    tx: EventSender,
 }
 
 impl EventSubscriber {
-    pub fn new(
+    fn new(
         methods: DashSet<String>,
         session_ids: DashSet<Arc<SessionId>>,
-        tx: mpsc::UnboundedSender<Arc<Event>>,
+        tx: EventSender,
     ) -> Self {
         Self {
             methods,
@@ -215,7 +496,7 @@ impl EventSubscriber {
                 return Ok(());
             }
         }
-        match self.tx.send(event) {
+        match self.tx.send(event).await {
             Ok(_) => Ok(()),
             Err(e) => {
                 //Leads to drop, cuz only Connection owns Arc, subscribe method returns Weak;
@@ -250,12 +531,27 @@ pub struct Connection {
     receiver_handle: Arc<RwLock<Option<JoinHandle<()>>>>,
     dispatcher_handle: Arc<RwLock<Option<JoinHandle<()>>>>,
     is_disconnecting: AtomicBool,
+    disconnect_started: AtomicBool,
+    close_pages_on_disconnect: AtomicBool,
     event_dispatcher: mpsc::UnboundedSender<Arc<Event>>,
+    //Events read from the socket which the dispatcher hasn't handed out yet
+    queued_events: AtomicUsize,
+    event_channel_mode: EventChannelMode,
+    request_timeout: Duration,
+    default_timeout: Duration,
 }
 
 impl Connection {
     pub async fn connect(ws_url: &str) -> Result<Arc<Self>> {
-        let (ws_stream, _) = connect_async(ws_url).await?;
+        Self::connect_with_options(ws_url, &ConnectionOptions::default()).await
+    }
+
+    pub async fn connect_with_options(
+        ws_url: &str,
+        options: &ConnectionOptions,
+    ) -> Result<Arc<Self>> {
+        let (ws_stream, _) =
+            connect_async_with_config(ws_url, Some(options.ws_config()), false).await?;
         let (mut ws_sink, mut ws_stream) = ws_stream.split();
         let (sender, mut rx) = mpsc::unbounded_channel::<Utf8Bytes>();
         let next_id = AtomicUsize::new(0);
@@ -275,7 +571,13 @@ impl Connection {
             receiver_handle: Arc::new(RwLock::new(None)),
             dispatcher_handle: Arc::new(RwLock::new(None)),
             is_disconnecting: AtomicBool::new(false),
+            disconnect_started: AtomicBool::new(false),
+            close_pages_on_disconnect: AtomicBool::new(false),
             event_dispatcher,
+            queued_events: AtomicUsize::new(0),
+            event_channel_mode: options.event_channel_mode,
+            request_timeout: options.request_timeout,
+            default_timeout: options.default_timeout,
         });
 
         let target_manager = TargetManager::new(conn.clone());
@@ -285,10 +587,35 @@ impl Connection {
             (*conn_ptr).target_manager = Some(Arc::new(target_manager));
         }
 
+        let protocol_logger = options.protocol_logger.clone();
+        let keepalive_interval = options.keepalive_interval;
         let _sender_handle = tokio::spawn(async move {
-            while let Some(request) = rx.recv().await {
-                if let Err(e) = ws_sink.send(WsMessage::Text(request)).await {
-                    eprintln!("Failed to send request: {}", e);
+            let mut keepalive = keepalive_interval.map(|period| {
+                tokio::time::interval_at(tokio::time::Instant::now() + period, period)
+            });
+            loop {
+                tokio::select! {
+                    request = rx.recv() => {
+                        let Some(request) = request else {
+                            break;
+                        };
+                        if let Some(protocol_logger) = &protocol_logger {
+                            protocol_logger.log(ProtocolDirection::Sent, request.as_str());
+                        }
+                        if let Err(e) = ws_sink.send(WsMessage::Text(request)).await {
+                            eprintln!("Failed to send request: {}", e);
+                        }
+                    }
+                    _ = async {
+                        match keepalive.as_mut() {
+                            Some(keepalive) => keepalive.tick().await,
+                            None => std::future::pending().await,
+                        }
+                    } => {
+                        if let Err(e) = ws_sink.send(WsMessage::Ping(Default::default())).await {
+                            eprintln!("Failed to send keepalive ping: {}", e);
+                        }
+                    }
                 }
                 // tokio::task::yield_now().await;
             }
@@ -340,6 +667,7 @@ impl Connection {
                     .collect();
 
                 join_all(unsubscribe_futures).await;
+                conn_clone.queued_events.fetch_sub(1, Ordering::SeqCst);
                 tokio::task::yield_now().await;
                 // });
             }
@@ -347,6 +675,7 @@ impl Connection {
 
         let conn_clone = conn.clone();
         let event_dispatcher = conn.event_dispatcher.clone();
+        let protocol_logger = options.protocol_logger.clone();
 
         let _receiver_handle = tokio::spawn(async move {
             while let Some(message) = ws_stream.next().await {
@@ -356,7 +685,12 @@ impl Connection {
                 // let event_dispatcher = event_dispatcher.clone();
                 // tokio::spawn(async move {
                 match message {
+                    //Pongs to keepalive pings and other control frames
+                    Ok(message) if !message.is_text() => continue,
                     Ok(message) => {
+                        if let Some(protocol_logger) = &protocol_logger {
+                            protocol_logger.log(ProtocolDirection::Received, &message.to_string());
+                        }
                         let message =
                             match serde_json::from_str::<IncomingMessage>(&message.to_string()) {
                                 Ok(msg) => msg,
@@ -377,7 +711,9 @@ impl Connection {
                             }
                             IncomingMessage::Event(event) => {
                                 let event = Arc::new(event);
+                                conn_clone.queued_events.fetch_add(1, Ordering::SeqCst);
                                 if let Err(e) = event_dispatcher.send(event) {
+                                    conn_clone.queued_events.fetch_sub(1, Ordering::SeqCst);
                                     eprintln!("Failed to dispatch event: {}", e);
                                 }
                             }
@@ -409,6 +745,10 @@ impl Connection {
         self.target_manager.as_ref()
     }
 
+    pub fn default_timeout(&self) -> Duration {
+        self.default_timeout
+    }
+
     pub async fn send<P: Serialize>(
         &self,
         method: &str,
@@ -437,7 +777,7 @@ impl Connection {
             Ok(_) => (),
             Err(e) => eprintln!("Failed to send request: {}", e),
         }
-        match tokio::time::timeout(tokio::time::Duration::from_millis(30000), rx).await {
+        match tokio::time::timeout(self.request_timeout, rx).await {
             Ok(Ok(response)) => Ok(response),
             Ok(Err(_)) => Err(anyhow!("Failed to receive response")),
             Err(_) => {
@@ -451,8 +791,26 @@ impl Connection {
         self: &Arc<Self>,
         methods: DashSet<String>,
         session_ids: DashSet<Arc<SessionId>>,
-    ) -> (Weak<EventSubscriber>, mpsc::UnboundedReceiver<Arc<Event>>) {
-        let (tx, rx) = mpsc::unbounded_channel();
+    ) -> (Weak<EventSubscriber>, EventReceiver) {
+        self.subscribe_with_mode(methods, session_ids, EventChannelMode::Unbounded)
+    }
+
+    ///Subscription for streams handed out to users, buffered by the connection's `EventChannelMode`.
+    pub async fn subscribe_stream(
+        self: &Arc<Self>,
+        methods: DashSet<String>,
+        session_ids: DashSet<Arc<SessionId>>,
+    ) -> (Weak<EventSubscriber>, EventReceiver) {
+        self.subscribe_with_mode(methods, session_ids, self.event_channel_mode)
+    }
+
+    fn subscribe_with_mode(
+        self: &Arc<Self>,
+        methods: DashSet<String>,
+        session_ids: DashSet<Arc<SessionId>>,
+        mode: EventChannelMode,
+    ) -> (Weak<EventSubscriber>, EventReceiver) {
+        let (tx, rx) = mode.channel();
         let sender_id = self.next_subscriber_id.fetch_add(1, Ordering::SeqCst);
         let event_subscriber = Arc::new(EventSubscriber::new(methods, session_ids, tx));
 
@@ -467,7 +825,26 @@ impl Connection {
         self.event_subscribers.remove(sender_id);
     }
 
+    ///Pages created through the target manager are closed before disconnecting.
+    pub fn set_close_pages_on_disconnect(&self, close: bool) {
+        self.close_pages_on_disconnect
+            .store(close, Ordering::SeqCst);
+    }
+
+    pub fn close_pages_on_disconnect(&self) -> bool {
+        self.close_pages_on_disconnect.load(Ordering::SeqCst)
+    }
+
     pub async fn disconnect(&self) {
+        //Browser and Chromatica drops can both get here, only the first one does the work
+        if self.disconnect_started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        if self.close_pages_on_disconnect()
+            && let Some(target_manager) = self.target_manager()
+        {
+            target_manager.close_created_pages().await;
+        }
         self.is_disconnecting.store(true, Ordering::SeqCst);
 
         match tokio::time::timeout(tokio::time::Duration::from_secs(3), async {
@@ -484,17 +861,246 @@ impl Connection {
             }
         }
 
-        let mut sender_handle = self.sender_handle.write().await;
-        if let Some(handle) = sender_handle.take() {
-            handle.abort();
-        }
+        //Nothing new is read from here on, events already read still reach subscribers,
+        //e.g. the targetDestroyed a closing page waits for.
         let mut receiver_handle = self.receiver_handle.write().await;
         if let Some(handle) = receiver_handle.take() {
             handle.abort();
         }
+        let _ = tokio::time::timeout(DISPATCHER_FLUSH_TIMEOUT, async {
+            while self.queued_events.load(Ordering::SeqCst) > 0 {
+                tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+            }
+        })
+        .await;
         let mut dispatcher_handle = self.dispatcher_handle.write().await;
         if let Some(handle) = dispatcher_handle.take() {
             handle.abort();
         }
+        let mut sender_handle = self.sender_handle.write().await;
+        if let Some(handle) = sender_handle.take() {
+            handle.abort();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::mock::MockBrowser;
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn routes_responses_and_errors_by_id() {
+        let browser = MockBrowser::start(|method, params, _| match method {
+            "Browser.getVersion" => Ok(json!({ "product": "Mock/1.0" })),
+            "Echo.params" => Ok(params.clone()),
+            _ => Err(format!("'{}' wasn't found", method)),
+        })
+        .await;
+        let connection = browser.connect().await;
+
+        let empty = json!({});
+        let value = json!({ "value": 42 });
+        let session_id = "abc".to_string();
+        let (version, echo, missing) = tokio::join!(
+            connection.send("Browser.getVersion", &empty, None),
+            connection.send("Echo.params", &value, Some(&session_id)),
+            connection.send("Missing.method", &empty, None),
+        );
+
+        assert_eq!(
+            version.unwrap().result_as::<Value>().unwrap()["product"],
+            "Mock/1.0"
+        );
+        let echo = echo.unwrap();
+        assert_eq!(echo.session_id.as_deref(), Some("abc"));
+        assert_eq!(echo.result_as::<Value>().unwrap()["value"], 42);
+        let error = missing.unwrap().result_as::<Value>().unwrap_err();
+        assert_eq!(error.to_string(), "'Missing.method' wasn't found");
+        assert_eq!(
+            error.downcast_ref::<CdpError>().map(|error| error.code),
+            Some(CdpError::SERVER_ERROR)
+        );
+
+        let echoed = browser.commands("Echo.params");
+        assert_eq!(echoed.len(), 1);
+        assert_eq!(echoed[0].session_id.as_deref(), Some("abc"));
+    }
+
+    #[tokio::test]
+    async fn delivers_events_to_matching_subscribers() {
+        let browser = MockBrowser::start(|_, _, _| Ok(json!({}))).await;
+        let connection = browser.connect().await;
+
+        let methods = DashSet::new();
+        methods.insert("Page.frameDetached".to_string());
+        let session_ids = DashSet::new();
+        session_ids.insert(Arc::new("session-1".to_string()));
+        let (_, mut rx) = connection.subscribe(methods, session_ids).await;
+
+        browser.emit(
+            "Page.frameDetached",
+            json!({ "frameId": "A" }),
+            Some("session-2"),
+        );
+        browser.emit(
+            "Page.frameAttached",
+            json!({ "frameId": "B", "parentFrameId": "A" }),
+            Some("session-1"),
+        );
+        browser.emit(
+            "Page.frameDetached",
+            json!({ "frameId": "C" }),
+            Some("session-1"),
+        );
+
+        let event = tokio::time::timeout(tokio::time::Duration::from_secs(5), rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(event.method, "Page.frameDetached");
+        assert_eq!(event.session_id.as_deref(), Some("session-1"));
+        match &event.params {
+            EventParams::FrameDetached(detached) => assert_eq!(detached.frame_id, "C"),
+            params => panic!("Unexpected params: {:?}", params),
+        }
+        assert!(rx.try_recv().is_err());
+
+        connection.disconnect().await;
+        let error = connection
+            .send("Browser.close", &json!({}), None)
+            .await
+            .unwrap_err();
+        assert_eq!(error.to_string(), "Connection is disconnecting");
+    }
+
+    #[tokio::test]
+    async fn logs_raw_protocol_messages() {
+        let browser = MockBrowser::start(|_, _, _| Ok(json!({ "ok": true }))).await;
+        let logged = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let logged_clone = logged.clone();
+        let options = ConnectionOptions::default()
+            .protocol_logger(Some(ProtocolLogger::new(move |direction, message| {
+                logged_clone
+                    .lock()
+                    .unwrap()
+                    .push((direction, message.to_string()));
+            })))
+            .build();
+        let connection = Connection::connect_with_options(browser.ws_url(), &options)
+            .await
+            .unwrap();
+
+        connection
+            .send("Browser.getVersion", &json!({}), None)
+            .await
+            .unwrap();
+
+        let logged = logged.lock().unwrap();
+        assert_eq!(logged.len(), 2);
+        assert_eq!(logged[0].0, ProtocolDirection::Sent);
+        assert!(logged[0].1.contains("Browser.getVersion"));
+        assert_eq!(logged[1].0, ProtocolDirection::Received);
+        assert!(logged[1].1.contains("\"ok\":true"));
+    }
+
+    #[tokio::test]
+    async fn drop_oldest_channel_keeps_latest_events() {
+        let browser = MockBrowser::start(|_, _, _| Ok(json!({}))).await;
+        let options = ConnectionOptions::default()
+            .event_channel_mode(EventChannelMode::DropOldest(2))
+            .build();
+        let connection = Connection::connect_with_options(browser.ws_url(), &options)
+            .await
+            .unwrap();
+
+        let methods = DashSet::new();
+        methods.insert("Page.frameDetached".to_string());
+        let (_, mut rx) = connection.subscribe_stream(methods, DashSet::new()).await;
+        let methods = DashSet::new();
+        methods.insert("Page.frameDetached".to_string());
+        let (_, mut internal_rx) = connection.subscribe(methods, DashSet::new()).await;
+        let methods = DashSet::new();
+        methods.insert("Page.frameAttached".to_string());
+        let (_, mut marker_rx) = connection.subscribe(methods, DashSet::new()).await;
+        for frame_id in ["A", "B", "C", "D"] {
+            browser.emit("Page.frameDetached", json!({ "frameId": frame_id }), None);
+        }
+        browser.emit(
+            "Page.frameAttached",
+            json!({ "frameId": "E", "parentFrameId": "A" }),
+            None,
+        );
+        //Events are dispatched in order, once the marker is here all detaches are buffered.
+        tokio::time::timeout(tokio::time::Duration::from_secs(5), marker_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+
+        let detached = |rx: &mut EventReceiver| {
+            let mut received = Vec::new();
+            while let Ok(event) = rx.try_recv() {
+                match &event.params {
+                    EventParams::FrameDetached(detached) => {
+                        received.push(detached.frame_id.clone())
+                    }
+                    params => panic!("Unexpected params: {:?}", params),
+                }
+            }
+            received
+        };
+        assert_eq!(detached(&mut rx), ["C", "D"]);
+        //Internal subscriptions are never bounded
+        assert_eq!(detached(&mut internal_rx), ["A", "B", "C", "D"]);
+    }
+
+    #[test]
+    fn malformed_response_is_an_error() {
+        let response: Response = serde_json::from_value(json!({ "id": 3 })).unwrap();
+        let error = response.result_as::<Value>().unwrap_err();
+        assert!(error.downcast_ref::<CdpError>().is_none());
+        assert_eq!(
+            error.to_string(),
+            "Malformed response to command 3: neither result nor error"
+        );
+    }
+
+    #[tokio::test]
+    async fn disconnect_delivers_events_already_received() {
+        let browser = MockBrowser::start(|_, _, _| Ok(json!({}))).await;
+        let options = ConnectionOptions::default()
+            .event_channel_mode(EventChannelMode::Backpressure(1))
+            .build();
+        let connection = Connection::connect_with_options(browser.ws_url(), &options)
+            .await
+            .unwrap();
+
+        let methods = DashSet::new();
+        methods.insert("Page.frameDetached".to_string());
+        let (_, mut rx) = connection.subscribe_stream(methods, DashSet::new()).await;
+        for frame_id in ["A", "B", "C", "D"] {
+            browser.emit("Page.frameDetached", json!({ "frameId": frame_id }), None);
+        }
+        //The dispatcher is stuck on the full channel with the rest queued behind it
+        super::super::mock::eventually(|| async {
+            connection.queued_events.load(Ordering::SeqCst) == 3
+        })
+        .await;
+
+        let disconnecting = connection.clone();
+        let disconnect = tokio::spawn(async move { disconnecting.disconnect().await });
+        let mut received = Vec::new();
+        while received.len() < 4 {
+            let event = tokio::time::timeout(tokio::time::Duration::from_secs(5), rx.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            if let EventParams::FrameDetached(detached) = &event.params {
+                received.push(detached.frame_id.clone());
+            }
+        }
+        disconnect.await.unwrap();
+        assert_eq!(received, ["A", "B", "C", "D"]);
     }
 }
@@ -0,0 +1,147 @@
+use super::connection::{EventParams, EventReceiver};
+use super::domains::runtime::{ConsoleAPICalled, ExceptionThrown, RemoteObject, Timestamp};
+use super::target::RuntimeGuard;
+use serde_json::Value;
+
+#[derive(Debug, Clone)]
+pub struct ConsoleMessage {
+    message_type: String,
+    text: String,
+    args: Vec<Value>,
+    timestamp: Timestamp,
+}
+
+impl ConsoleMessage {
+    pub fn new(event: &ConsoleAPICalled) -> Self {
+        let text = event
+            .args
+            .iter()
+            .map(remote_object_to_string)
+            .collect::<Vec<_>>()
+            .join(" ");
+        let args = event
+            .args
+            .iter()
+            .map(|arg| arg.value.clone().unwrap_or(Value::Null))
+            .collect();
+
+        Self {
+            message_type: event.type_name.clone(),
+            text,
+            args,
+            timestamp: event.timestamp,
+        }
+    }
+
+    ///log, info, warning, error, debug, etc.
+    pub fn message_type(&self) -> &str {
+        &self.message_type
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    ///Primitive arguments by value, objects are `Null`, their description is in `text`.
+    pub fn args(&self) -> &[Value] {
+        &self.args
+    }
+
+    pub fn timestamp(&self) -> Timestamp {
+        self.timestamp
+    }
+}
+
+fn remote_object_to_string(object: &RemoteObject) -> String {
+    match &object.value {
+        Some(Value::String(value)) => value.clone(),
+        Some(value) => value.to_string(),
+        None => object
+            .unserializable_value
+            .clone()
+            .or_else(|| object.description.clone())
+            .unwrap_or_else(|| object.type_name.clone()),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PageError {
+    message: String,
+    line_number: i32,
+    column_number: i32,
+    timestamp: Timestamp,
+}
+
+impl PageError {
+    pub fn new(event: &ExceptionThrown) -> Self {
+        Self {
+            message: event.exception_details.message(),
+            line_number: event.exception_details.line_number,
+            column_number: event.exception_details.column_number,
+            timestamp: event.timestamp,
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn line_number(&self) -> i32 {
+        self.line_number
+    }
+
+    pub fn column_number(&self) -> i32 {
+        self.column_number
+    }
+
+    pub fn timestamp(&self) -> Timestamp {
+        self.timestamp
+    }
+}
+
+//Both streams hold RuntimeGuard, so Runtime stays enabled only while somebody listens.
+pub struct ConsoleStream {
+    receiver: EventReceiver,
+    _runtime: RuntimeGuard,
+}
+
+impl ConsoleStream {
+    pub fn new(receiver: EventReceiver, runtime: RuntimeGuard) -> Self {
+        Self {
+            receiver,
+            _runtime: runtime,
+        }
+    }
+
+    pub async fn next(&mut self) -> Option<ConsoleMessage> {
+        while let Some(event) = self.receiver.recv().await {
+            if let EventParams::ConsoleAPICalled(console_api_called) = &event.params {
+                return Some(ConsoleMessage::new(console_api_called));
+            }
+        }
+        None
+    }
+}
+
+pub struct PageErrorStream {
+    receiver: EventReceiver,
+    _runtime: RuntimeGuard,
+}
+
+impl PageErrorStream {
+    pub fn new(receiver: EventReceiver, runtime: RuntimeGuard) -> Self {
+        Self {
+            receiver,
+            _runtime: runtime,
+        }
+    }
+
+    pub async fn next(&mut self) -> Option<PageError> {
+        while let Some(event) = self.receiver.recv().await {
+            if let EventParams::ExceptionThrown(exception_thrown) = &event.params {
+                return Some(PageError::new(exception_thrown));
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,190 @@
+use super::domains::network::Cookie;
+use anyhow::{Result, anyhow};
+
+//curl and browser extensions mark HttpOnly cookies by prefixing the domain, the line isn't a comment
+const HTTP_ONLY_PREFIX: &str = "#HttpOnly_";
+
+///Cookie read from a jar. Host-only cookies have `url` set, they must be set by it instead of the domain,
+///which Chrome would turn into a domain cookie sent to subdomains too.
+#[derive(Debug)]
+pub struct JarCookie {
+    pub cookie: Cookie,
+    pub url: Option<String>,
+}
+
+///Parses a Netscape cookie file (cookies.txt) or a `Cookie:` header string.
+///Header cookies have no domain, they are meant to be set for the current page url.
+pub fn parse_cookies(jar: &str) -> Result<Vec<JarCookie>> {
+    let is_netscape = jar
+        .lines()
+        .any(|line| line.starts_with(HTTP_ONLY_PREFIX) || line.split('\t').count() == 7);
+    if is_netscape {
+        parse_netscape(jar)
+    } else {
+        parse_header(jar)
+    }
+}
+
+fn parse_netscape(jar: &str) -> Result<Vec<JarCookie>> {
+    let mut cookies = Vec::new();
+    for (index, line) in jar.lines().enumerate() {
+        let line = line.trim_end_matches('\r');
+        let (line, http_only) = match line.strip_prefix(HTTP_ONLY_PREFIX) {
+            Some(line) => (line, true),
+            None => (line, false),
+        };
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split('\t').collect();
+        let [
+            domain,
+            include_subdomains,
+            path,
+            secure,
+            expires,
+            name,
+            value,
+        ] = fields[..]
+        else {
+            return Err(anyhow!(
+                "Invalid cookie jar line {}: expected 7 tab separated fields",
+                index + 1
+            ));
+        };
+        let expires: f64 = expires
+            .parse()
+            .map_err(|_| anyhow!("Invalid cookie jar line {}: bad expiry", index + 1))?;
+
+        //Zero expiry is a session cookie
+        let expires = if expires > 0.0 { Some(expires) } else { None };
+        let secure = secure.eq_ignore_ascii_case("TRUE");
+        let url = match include_subdomains.eq_ignore_ascii_case("TRUE") {
+            true => None,
+            false => Some(format!(
+                "{}://{}{}",
+                if secure { "https" } else { "http" },
+                domain.trim_start_matches('.'),
+                path
+            )),
+        };
+        cookies.push(JarCookie {
+            cookie: cookie(name, value, domain, path, secure, http_only, expires),
+            url,
+        });
+    }
+    Ok(cookies)
+}
+
+fn parse_header(header: &str) -> Result<Vec<JarCookie>> {
+    let header = header.trim();
+    let header = match header.split_once(':') {
+        Some((name, value)) if name.trim().eq_ignore_ascii_case("cookie") => value,
+        _ => header,
+    };
+
+    let mut cookies = Vec::new();
+    for pair in header.split(';') {
+        let pair = pair.trim();
+        if pair.is_empty() {
+            continue;
+        }
+        let Some((name, value)) = pair.split_once('=') else {
+            return Err(anyhow!("Invalid cookie pair: {}", pair));
+        };
+        cookies.push(JarCookie {
+            cookie: cookie(name.trim(), value.trim(), "", "/", false, false, None),
+            url: None,
+        });
+    }
+    Ok(cookies)
+}
+
+fn cookie(
+    name: &str,
+    value: &str,
+    domain: &str,
+    path: &str,
+    secure: bool,
+    http_only: bool,
+    expires: Option<f64>,
+) -> Cookie {
+    Cookie {
+        name: name.to_string(),
+        value: value.to_string(),
+        domain: domain.to_string(),
+        path: path.to_string(),
+        expires,
+        size: (name.len() + value.len()) as i32,
+        http_only,
+        secure,
+        session: expires.is_none(),
+        same_site: None,
+        priority: None,
+        same_party: false,
+        source_scheme: None,
+        source_port: -1,
+        partition_key: None,
+        partition_key_opaque: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_netscape_jar() {
+        let jar = "# Netscape HTTP Cookie File\n\
+                   \n\
+                   .example.com\tTRUE\t/\tTRUE\t1900000000\tsid\tabc\n\
+                   #HttpOnly_example.com\tFALSE\t/app\tFALSE\t0\ttoken\tx=y\n";
+        let cookies = parse_cookies(jar).unwrap();
+        assert_eq!(cookies.len(), 2);
+
+        assert_eq!(cookies[0].cookie.domain, ".example.com");
+        assert_eq!(cookies[0].cookie.name, "sid");
+        assert!(cookies[0].cookie.secure);
+        assert!(!cookies[0].cookie.http_only);
+        assert_eq!(cookies[0].cookie.expires, Some(1900000000.0));
+        //Subdomains included, so it's set by its domain
+        assert_eq!(cookies[0].url, None);
+
+        assert_eq!(cookies[1].cookie.domain, "example.com");
+        assert_eq!(cookies[1].cookie.path, "/app");
+        assert_eq!(cookies[1].cookie.value, "x=y");
+        assert!(cookies[1].cookie.http_only);
+        assert!(cookies[1].cookie.session);
+        //Host-only, set by url so subdomains don't get it
+        assert_eq!(cookies[1].url.as_deref(), Some("http://example.com/app"));
+    }
+
+    #[test]
+    fn parses_cookie_header() {
+        let cookies = parse_cookies("Cookie: a=1; b = two;").unwrap();
+        assert_eq!(cookies.len(), 2);
+        assert_eq!(
+            (
+                cookies[0].cookie.name.as_str(),
+                cookies[0].cookie.value.as_str()
+            ),
+            ("a", "1")
+        );
+        assert_eq!(
+            (
+                cookies[1].cookie.name.as_str(),
+                cookies[1].cookie.value.as_str()
+            ),
+            ("b", "two")
+        );
+        assert!(cookies[1].cookie.domain.is_empty());
+        assert_eq!(cookies[1].url, None);
+    }
+
+    #[test]
+    fn rejects_malformed_jar_line() {
+        let jar = "example.com\tFALSE\t/\tFALSE\tnever\tname\tvalue";
+        assert!(parse_cookies(jar).is_err());
+    }
+}
@@ -72,7 +72,7 @@ impl<'a> CancelDownload<'a> {
     }
 }
 
-#[derive(Serialize)]
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
 pub enum DownloadBehavior {
     #[serde(rename = "deny")]
     Deny,
@@ -55,9 +55,13 @@ pub struct FullNode {
     pub parent_id: Option<NodeId>,
     pub backend_node_id: BackendNodeId,
     pub node_type: i32,
+    #[serde(default)]
+    pub node_name: String,
     pub local_name: String,
     pub node_value: String,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub attributes: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub children: Option<Vec<FullNode>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub frame_id: Option<FrameId>,
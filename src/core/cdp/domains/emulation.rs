@@ -173,3 +173,101 @@ impl SetUserAgentOverride {
         self
     }
 }
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SetDeviceMetricsOverride {
+    pub width: u32,
+    pub height: u32,
+    pub device_scale_factor: f64,
+    pub mobile: bool,
+}
+
+impl SetDeviceMetricsOverride {
+    pub fn default(width: u32, height: u32, device_scale_factor: f64, mobile: bool) -> Self {
+        Self {
+            width,
+            height,
+            device_scale_factor,
+            mobile,
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct SetScriptExecutionDisabled {
+    pub value: bool,
+}
+
+impl SetScriptExecutionDisabled {
+    pub fn default(value: bool) -> Self {
+        Self { value }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct SetEmulatedMedia<'a> {
+    pub media: &'a str,
+}
+
+impl<'a> SetEmulatedMedia<'a> {
+    ///Empty media disables the override.
+    pub fn default(media: &'a str) -> Self {
+        Self { media }
+    }
+}
+
+///All fields None clears the override.
+#[derive(Serialize, Debug)]
+pub struct SetGeolocationOverride {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latitude: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub longitude: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accuracy: Option<f64>,
+}
+
+impl SetGeolocationOverride {
+    pub fn default(latitude: f64, longitude: f64, accuracy: f64) -> Self {
+        Self {
+            latitude: Some(latitude),
+            longitude: Some(longitude),
+            accuracy: Some(accuracy),
+        }
+    }
+
+    pub fn clear() -> Self {
+        Self {
+            latitude: None,
+            longitude: None,
+            accuracy: None,
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SetTimezoneOverride<'a> {
+    pub timezone_id: &'a str,
+}
+
+impl<'a> SetTimezoneOverride<'a> {
+    ///Empty timezone id disables the override.
+    pub fn default(timezone_id: &'a str) -> Self {
+        Self { timezone_id }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct SetLocaleOverride<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locale: Option<&'a str>,
+}
+
+impl<'a> SetLocaleOverride<'a> {
+    ///None restores the system locale.
+    pub fn default(locale: Option<&'a str>) -> Self {
+        Self { locale }
+    }
+}
@@ -154,7 +154,7 @@ pub struct ResponseBody {
 pub struct RequestPaused {
     pub request_id: RequestId,
     pub request: Request,
-    // pub frame_id: FrameId,
+    pub frame_id: Option<FrameId>,
     pub resource_type: ResourceType,
     pub response_error_reason: Option<ErrorReason>,
     pub response_status_code: Option<i32>,
@@ -604,3 +604,99 @@ impl DispatchTouchEvent {
         self
     }
 }
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SynthesizeScrollGesture {
+    pub x: f64,
+    pub y: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub x_distance: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub y_distance: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub x_overscroll: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub y_overscroll: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prevent_fling: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speed: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gesture_source_type: Option<GestureSourceType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repeat_count: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repeat_delay_ms: Option<i32>,
+}
+
+impl SynthesizeScrollGesture {
+    pub fn default(x: f64, y: f64) -> Self {
+        Self {
+            x,
+            y,
+            x_distance: None,
+            y_distance: None,
+            x_overscroll: None,
+            y_overscroll: None,
+            prevent_fling: None,
+            speed: None,
+            gesture_source_type: None,
+            repeat_count: None,
+            repeat_delay_ms: None,
+        }
+    }
+
+    pub fn new(x: f64, y: f64) -> Self {
+        Self::default(x, y)
+    }
+
+    pub fn x_distance(mut self, x_distance: f64) -> Self {
+        self.x_distance = Some(x_distance);
+        self
+    }
+
+    pub fn y_distance(mut self, y_distance: f64) -> Self {
+        self.y_distance = Some(y_distance);
+        self
+    }
+
+    pub fn x_overscroll(mut self, x_overscroll: f64) -> Self {
+        self.x_overscroll = Some(x_overscroll);
+        self
+    }
+
+    pub fn y_overscroll(mut self, y_overscroll: f64) -> Self {
+        self.y_overscroll = Some(y_overscroll);
+        self
+    }
+
+    pub fn prevent_fling(mut self, prevent_fling: bool) -> Self {
+        self.prevent_fling = Some(prevent_fling);
+        self
+    }
+
+    pub fn speed(mut self, speed: i32) -> Self {
+        self.speed = Some(speed);
+        self
+    }
+
+    pub fn gesture_source_type(mut self, gesture_source_type: GestureSourceType) -> Self {
+        self.gesture_source_type = Some(gesture_source_type);
+        self
+    }
+
+    pub fn repeat_count(mut self, repeat_count: i32) -> Self {
+        self.repeat_count = Some(repeat_count);
+        self
+    }
+
+    pub fn repeat_delay_ms(mut self, repeat_delay_ms: i32) -> Self {
+        self.repeat_delay_ms = Some(repeat_delay_ms);
+        self
+    }
+
+    pub fn build(self) -> Self {
+        self
+    }
+}
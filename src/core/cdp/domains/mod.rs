@@ -8,3 +8,4 @@ pub mod network;
 pub mod page;
 pub mod runtime;
 pub mod target;
+pub mod web_authn;
@@ -10,6 +10,86 @@ pub type MonotonicTime = f64;
 ///https://chromedevtools.github.io/devtools-protocol/tot/Network/#type-ResourceType
 pub type ResourceType = String;
 
+///Typed `ResourceType`, so a misspelled type is a compile error instead of a comparison that never matches.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ResourceKind {
+    Document,
+    Stylesheet,
+    Image,
+    Media,
+    Font,
+    Script,
+    TextTrack,
+    XHR,
+    Fetch,
+    Prefetch,
+    EventSource,
+    WebSocket,
+    Manifest,
+    SignedExchange,
+    Ping,
+    CSPViolationReport,
+    Preflight,
+    FedCM,
+    Other,
+    ///Type added to the protocol after this enum was written.
+    Unknown(String),
+}
+
+impl ResourceKind {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Document => "Document",
+            Self::Stylesheet => "Stylesheet",
+            Self::Image => "Image",
+            Self::Media => "Media",
+            Self::Font => "Font",
+            Self::Script => "Script",
+            Self::TextTrack => "TextTrack",
+            Self::XHR => "XHR",
+            Self::Fetch => "Fetch",
+            Self::Prefetch => "Prefetch",
+            Self::EventSource => "EventSource",
+            Self::WebSocket => "WebSocket",
+            Self::Manifest => "Manifest",
+            Self::SignedExchange => "SignedExchange",
+            Self::Ping => "Ping",
+            Self::CSPViolationReport => "CSPViolationReport",
+            Self::Preflight => "Preflight",
+            Self::FedCM => "FedCM",
+            Self::Other => "Other",
+            Self::Unknown(resource_type) => resource_type,
+        }
+    }
+}
+
+impl From<&str> for ResourceKind {
+    fn from(resource_type: &str) -> Self {
+        match resource_type {
+            "Document" => Self::Document,
+            "Stylesheet" => Self::Stylesheet,
+            "Image" => Self::Image,
+            "Media" => Self::Media,
+            "Font" => Self::Font,
+            "Script" => Self::Script,
+            "TextTrack" => Self::TextTrack,
+            "XHR" => Self::XHR,
+            "Fetch" => Self::Fetch,
+            "Prefetch" => Self::Prefetch,
+            "EventSource" => Self::EventSource,
+            "WebSocket" => Self::WebSocket,
+            "Manifest" => Self::Manifest,
+            "SignedExchange" => Self::SignedExchange,
+            "Ping" => Self::Ping,
+            "CSPViolationReport" => Self::CSPViolationReport,
+            "Preflight" => Self::Preflight,
+            "FedCM" => Self::FedCM,
+            "Other" => Self::Other,
+            _ => Self::Unknown(resource_type.to_string()),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub enum ErrorReason {
     #[serde(rename = "Failed")]
@@ -113,17 +193,29 @@ pub struct Cookie {
 pub struct CookieParams<'a> {
     pub name: &'a str,
     pub value: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub url: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub domain: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub path: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub secure: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub http_only: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub same_site: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub expires: Option<TimeSinceEpoch>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub priority: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub same_party: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub source_scheme: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub source_port: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub partition_key: Option<&'a CookiePartitionKey>,
 }
 
@@ -186,6 +278,23 @@ pub struct LoadingFailed {
     pub error_text: String,
     #[serde(rename = "type")]
     pub recource_type: ResourceType,
+    pub canceled: Option<bool>,
+    pub blocked_reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestWillBeSent {
+    pub request_id: RequestId,
+    pub request: Request,
+    #[serde(rename = "type")]
+    pub resource_type: Option<ResourceType>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadingFinished {
+    pub request_id: RequestId,
 }
 
 #[derive(Serialize)]
@@ -411,3 +520,19 @@ impl<'a> SetCookies<'a> {
         Self { cookies }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resource_kind_round_trips_protocol_names() {
+        assert_eq!(ResourceKind::from("Image"), ResourceKind::Image);
+        assert_eq!(ResourceKind::from("XHR").as_str(), "XHR");
+        assert_eq!(
+            ResourceKind::from("Speculation"),
+            ResourceKind::Unknown("Speculation".to_string())
+        );
+        assert_eq!(ResourceKind::from("Speculation").as_str(), "Speculation");
+    }
+}
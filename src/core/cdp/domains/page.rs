@@ -53,6 +53,19 @@ pub struct CaptureScreenshotResponse {
     pub data: String,
 }
 
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LayoutViewport {
+    pub page_x: f32,
+    pub page_y: f32,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GetLayoutMetricsResponse {
+    pub css_layout_viewport: LayoutViewport,
+}
+
 #[derive(Debug, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct PrintToPDFResponse {
@@ -428,6 +441,56 @@ impl<'a> Navigate<'a> {
     }
 }
 
+///Common paper sizes. Portrait dimensions, `landscape` rotates them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaperSize {
+    A3,
+    A4,
+    Letter,
+    Legal,
+    Tabloid,
+}
+
+impl PaperSize {
+    ///Width and height in inches, as PrintToPDF expects them.
+    pub fn dimensions(&self) -> (f64, f64) {
+        match self {
+            Self::A3 => (297.0 / 25.4, 420.0 / 25.4),
+            Self::A4 => (210.0 / 25.4, 297.0 / 25.4),
+            Self::Letter => (8.5, 11.0),
+            Self::Legal => (8.5, 14.0),
+            Self::Tabloid => (11.0, 17.0),
+        }
+    }
+}
+
+///Margin presets, applied to all four sides unless noted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Margins {
+    None,
+    ///1cm, what Chrome uses when no margin is set.
+    Default,
+    ///0.5 inch.
+    Narrow,
+    ///1 inch top and bottom, 2 inches left and right.
+    Wide,
+}
+
+impl Margins {
+    ///Top, bottom, left and right margin in inches.
+    pub fn inches(&self) -> (f64, f64, f64, f64) {
+        match self {
+            Self::None => (0.0, 0.0, 0.0, 0.0),
+            Self::Default => {
+                let cm = 1.0 / 2.54;
+                (cm, cm, cm, cm)
+            }
+            Self::Narrow => (0.5, 0.5, 0.5, 0.5),
+            Self::Wide => (1.0, 1.0, 2.0, 2.0),
+        }
+    }
+}
+
 #[derive(Serialize)]
 pub struct PrintToPDF<'a> {
     #[serde(rename = "landscape", skip_serializing_if = "Option::is_none")]
@@ -539,6 +602,23 @@ impl<'a> PrintToPDF<'a> {
         self
     }
 
+    ///Sets both paper width and height. They win over CSS `@page { size }` unless `prefer_css_page_size` is set.
+    pub fn paper_size(mut self, paper_size: PaperSize) -> Self {
+        let (width, height) = paper_size.dimensions();
+        self.paper_width = Some(width);
+        self.paper_height = Some(height);
+        self
+    }
+
+    pub fn margins(mut self, margins: Margins) -> Self {
+        let (top, bottom, left, right) = margins.inches();
+        self.margin_top = Some(top);
+        self.margin_bottom = Some(bottom);
+        self.margin_left = Some(left);
+        self.margin_right = Some(right);
+        self
+    }
+
     pub fn margin_top(mut self, value: f64) -> Self {
         self.margin_top = Some(value);
         self
@@ -579,11 +659,21 @@ impl<'a> PrintToPDF<'a> {
         self
     }
 
+    ///Without it CSS `@page { size }` is ignored and the page is Letter or the explicit paper size.
     pub fn prefer_css_page_size(mut self, value: bool) -> Self {
         self.prefer_css_page_size = Some(value);
         self
     }
 
+    ///Takes the page size from CSS `@page { size }` and clears explicit paper dimensions, so documents without
+    ///one are printed on Letter. Calling `paper_size(..)` afterwards sets a fallback size for such documents.
+    pub fn use_css_page_size(mut self) -> Self {
+        self.prefer_css_page_size = Some(true);
+        self.paper_width = None;
+        self.paper_height = None;
+        self
+    }
+
     pub fn build(self) -> Self {
         self
     }
@@ -677,6 +767,7 @@ impl SetBypassCSP {
 }
 
 #[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct SetDocumentContent<'a> {
     pub frame_id: &'a FrameId,
     pub html: &'a str,
@@ -756,6 +847,15 @@ impl GetNavigationHistory {
     }
 }
 
+#[derive(Serialize)]
+pub struct GetLayoutMetrics {}
+
+impl GetLayoutMetrics {
+    pub fn default() -> Self {
+        Self {}
+    }
+}
+
 #[derive(Deserialize)]
 pub struct GetNavigationHistoryResponse {
     #[serde(rename = "currentIndex")]
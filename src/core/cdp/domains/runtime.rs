@@ -14,11 +14,32 @@ pub type UnserializableValue = String;
 #[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct CallArgument {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub value: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub unserializable_value: Option<UnserializableValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub object_id: Option<RemoteObjectId>,
 }
 
+impl CallArgument {
+    pub fn from_value(value: Value) -> Self {
+        Self {
+            value: Some(value),
+            unserializable_value: None,
+            object_id: None,
+        }
+    }
+
+    pub fn from_object_id(object_id: RemoteObjectId) -> Self {
+        Self {
+            value: None,
+            unserializable_value: None,
+            object_id: Some(object_id),
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct CallFrame {
@@ -103,7 +124,7 @@ pub struct ExceptionDetails {
     // pub script_id: ScriptId,
     // pub url: Option<String>,
     // pub stack_trace: Option<StackTrace>,
-    // pub exception: Option<RemoteObject>,
+    pub exception: Option<RemoteObject>,
     // pub execution_context_id: Option<i32>,
     // pub exception_meta_data: Option<Value>,
 }
@@ -115,6 +136,40 @@ pub struct EvaluateResponse {
     pub exception_details: Option<ExceptionDetails>,
 }
 
+impl ExceptionDetails {
+    ///`text` is usually just "Uncaught", the thrown value carries the actual message.
+    pub fn message(&self) -> String {
+        match self
+            .exception
+            .as_ref()
+            .and_then(|exception| exception.description.clone())
+        {
+            Some(description) => description,
+            None => self.text.clone(),
+        }
+    }
+}
+
+pub type CallFunctionOnResponse = EvaluateResponse;
+
+///Deserializable events
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsoleAPICalled {
+    #[serde(rename = "type")]
+    pub type_name: String, // log, debug, info, error, warning, dir, table, trace, assert, ...
+    pub args: Vec<RemoteObject>,
+    pub execution_context_id: ExecutionContextId,
+    pub timestamp: Timestamp,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExceptionThrown {
+    pub timestamp: Timestamp,
+    pub exception_details: ExceptionDetails,
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct GetPropertiesResponse {
@@ -673,3 +728,15 @@ impl<'a> ReleaseObject<'a> {
         Self { object_id }
     }
 }
+
+#[derive(Serialize)]
+pub struct ReleaseObjectGroup<'a> {
+    #[serde(rename = "objectGroup")]
+    pub object_group: &'a str,
+}
+
+impl<'a> ReleaseObjectGroup<'a> {
+    pub fn default(object_group: &'a str) -> Self {
+        Self { object_group }
+    }
+}
@@ -0,0 +1,236 @@
+use serde::{Deserialize, Serialize};
+
+pub type AuthenticatorId = String;
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub enum AuthenticatorProtocol {
+    #[serde(rename = "u2f")]
+    U2f,
+    #[serde(rename = "ctap2")]
+    Ctap2,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub enum AuthenticatorTransport {
+    #[serde(rename = "usb")]
+    Usb,
+    #[serde(rename = "nfc")]
+    Nfc,
+    #[serde(rename = "ble")]
+    Ble,
+    #[serde(rename = "cable")]
+    Cable,
+    #[serde(rename = "internal")]
+    Internal,
+}
+
+///Credential ids, private keys (PKCS#8), user handles and large blobs are base64 encoded.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Credential {
+    pub credential_id: String,
+    pub is_resident_credential: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rp_id: Option<String>,
+    pub private_key: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_handle: Option<String>,
+    pub sign_count: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub large_blob: Option<String>,
+}
+
+impl Credential {
+    pub fn new(credential_id: &str, private_key: &str) -> Self {
+        Self {
+            credential_id: credential_id.to_string(),
+            is_resident_credential: false,
+            rp_id: None,
+            private_key: private_key.to_string(),
+            user_handle: None,
+            sign_count: 0,
+            large_blob: None,
+        }
+    }
+
+    pub fn is_resident_credential(mut self, is_resident_credential: bool) -> Self {
+        self.is_resident_credential = is_resident_credential;
+        self
+    }
+
+    pub fn rp_id(mut self, rp_id: &str) -> Self {
+        self.rp_id = Some(rp_id.to_string());
+        self
+    }
+
+    pub fn user_handle(mut self, user_handle: &str) -> Self {
+        self.user_handle = Some(user_handle.to_string());
+        self
+    }
+
+    pub fn sign_count(mut self, sign_count: u32) -> Self {
+        self.sign_count = sign_count;
+        self
+    }
+
+    pub fn large_blob(mut self, large_blob: &str) -> Self {
+        self.large_blob = Some(large_blob.to_string());
+        self
+    }
+
+    pub fn build(self) -> Self {
+        self
+    }
+}
+
+///Deserializable structs
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AddVirtualAuthenticatorResponse {
+    pub authenticator_id: AuthenticatorId,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GetCredentialsResponse {
+    pub credentials: Vec<Credential>,
+}
+
+///Serializable structs for requests
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct VirtualAuthenticatorOptions {
+    pub protocol: AuthenticatorProtocol,
+    pub transport: AuthenticatorTransport,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub has_resident_key: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub has_user_verification: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub has_large_blob: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub automatic_presence_simulation: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_user_verified: Option<bool>,
+}
+
+impl VirtualAuthenticatorOptions {
+    ///Platform authenticator with resident keys and user verification, i.e. what passkeys expect.
+    pub fn default() -> Self {
+        Self {
+            protocol: AuthenticatorProtocol::Ctap2,
+            transport: AuthenticatorTransport::Internal,
+            has_resident_key: Some(true),
+            has_user_verification: Some(true),
+            has_large_blob: None,
+            automatic_presence_simulation: Some(true),
+            is_user_verified: Some(true),
+        }
+    }
+
+    pub fn new(protocol: AuthenticatorProtocol, transport: AuthenticatorTransport) -> Self {
+        Self {
+            protocol,
+            transport,
+            has_resident_key: None,
+            has_user_verification: None,
+            has_large_blob: None,
+            automatic_presence_simulation: None,
+            is_user_verified: None,
+        }
+    }
+
+    pub fn has_resident_key(mut self, has_resident_key: bool) -> Self {
+        self.has_resident_key = Some(has_resident_key);
+        self
+    }
+
+    pub fn has_user_verification(mut self, has_user_verification: bool) -> Self {
+        self.has_user_verification = Some(has_user_verification);
+        self
+    }
+
+    pub fn has_large_blob(mut self, has_large_blob: bool) -> Self {
+        self.has_large_blob = Some(has_large_blob);
+        self
+    }
+
+    pub fn automatic_presence_simulation(mut self, automatic_presence_simulation: bool) -> Self {
+        self.automatic_presence_simulation = Some(automatic_presence_simulation);
+        self
+    }
+
+    pub fn is_user_verified(mut self, is_user_verified: bool) -> Self {
+        self.is_user_verified = Some(is_user_verified);
+        self
+    }
+
+    pub fn build(self) -> Self {
+        self
+    }
+}
+
+#[derive(Serialize)]
+pub struct WebAuthnEnable {
+    #[serde(rename = "enableUI", skip_serializing_if = "Option::is_none")]
+    pub enable_ui: Option<bool>,
+}
+
+impl WebAuthnEnable {
+    pub fn default() -> Self {
+        Self { enable_ui: None }
+    }
+}
+
+#[derive(Serialize)]
+pub struct AddVirtualAuthenticator<'a> {
+    #[serde(rename = "options")]
+    pub options: &'a VirtualAuthenticatorOptions,
+}
+
+impl<'a> AddVirtualAuthenticator<'a> {
+    pub fn default(options: &'a VirtualAuthenticatorOptions) -> Self {
+        Self { options }
+    }
+}
+
+#[derive(Serialize)]
+pub struct RemoveVirtualAuthenticator<'a> {
+    #[serde(rename = "authenticatorId")]
+    pub authenticator_id: &'a AuthenticatorId,
+}
+
+impl<'a> RemoveVirtualAuthenticator<'a> {
+    pub fn default(authenticator_id: &'a AuthenticatorId) -> Self {
+        Self { authenticator_id }
+    }
+}
+
+#[derive(Serialize)]
+pub struct AddCredential<'a> {
+    #[serde(rename = "authenticatorId")]
+    pub authenticator_id: &'a AuthenticatorId,
+    #[serde(rename = "credential")]
+    pub credential: &'a Credential,
+}
+
+impl<'a> AddCredential<'a> {
+    pub fn default(authenticator_id: &'a AuthenticatorId, credential: &'a Credential) -> Self {
+        Self {
+            authenticator_id,
+            credential,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct GetCredentials<'a> {
+    #[serde(rename = "authenticatorId")]
+    pub authenticator_id: &'a AuthenticatorId,
+}
+
+impl<'a> GetCredentials<'a> {
+    pub fn default(authenticator_id: &'a AuthenticatorId) -> Self {
+        Self { authenticator_id }
+    }
+}
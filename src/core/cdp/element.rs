@@ -1,16 +1,67 @@
+use futures::stream::BoxStream;
 use std::collections::HashMap;
-use std::sync::{Arc, Weak};
+use std::fmt;
+use std::sync::{Arc, OnceLock, Weak};
 use tokio::time::Duration;
 
 use super::domains::dom::BackendNodeId;
+use super::domains::page::PrintToPDF;
 use super::frame_inner::FrameInner;
+use super::page::ScreenshotOptions;
 
 use anyhow::{Result, anyhow};
+use serde::Serialize;
 
-#[derive(Debug, Clone)]
+///Options of `Element.checkVisibility()`. With none set only a missing box counts as hidden,
+///e.g. `display: none` or a skipped `content-visibility: hidden` subtree.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VisibilityOptions {
+    ///`opacity: 0` counts as hidden.
+    pub check_opacity: bool,
+    ///`visibility: hidden` or `collapse` counts as hidden.
+    #[serde(rename = "checkVisibilityCSS")]
+    pub check_visibility_css: bool,
+    ///Content skipped by `content-visibility: auto` counts as hidden.
+    pub content_visibility_auto: bool,
+}
+
+impl VisibilityOptions {
+    ///Every check enabled, what a user would call visible.
+    pub fn all() -> Self {
+        Self {
+            check_opacity: true,
+            check_visibility_css: true,
+            content_visibility_auto: true,
+        }
+    }
+
+    pub fn check_opacity(mut self, check_opacity: bool) -> Self {
+        self.check_opacity = check_opacity;
+        self
+    }
+
+    pub fn check_visibility_css(mut self, check_visibility_css: bool) -> Self {
+        self.check_visibility_css = check_visibility_css;
+        self
+    }
+
+    pub fn content_visibility_auto(mut self, content_visibility_auto: bool) -> Self {
+        self.content_visibility_auto = content_visibility_auto;
+        self
+    }
+
+    pub fn build(self) -> Self {
+        self
+    }
+}
+
+#[derive(Clone)]
 pub struct Element {
     frame_inner: Weak<FrameInner>,
     backend_node_id: BackendNodeId,
+    //Filled by the first `describe`, shared between clones
+    description: Arc<OnceLock<String>>,
 }
 
 impl Element {
@@ -18,10 +69,28 @@ impl Element {
         Self {
             frame_inner,
             backend_node_id,
+            description: Arc::new(OnceLock::new()),
         }
     }
 
-    fn frame_inner(&self) -> Option<Arc<FrameInner>> {
+    ///Tag, id and classes like `<button#submit.primary>`. Fetched once, later DOM changes aren't reflected.
+    pub async fn describe(&self) -> Result<String> {
+        if let Some(description) = self.description.get() {
+            return Ok(description.clone());
+        }
+        let frame_inner = match self.frame_inner() {
+            Some(frame_inner) => frame_inner,
+            None => return Err(anyhow!("Frame inner is not available")),
+        };
+        let description = frame_inner.describe_element(&self.backend_node_id).await?;
+        Ok(self.description.get_or_init(|| description).clone())
+    }
+
+    pub(crate) fn backend_node_id(&self) -> BackendNodeId {
+        self.backend_node_id
+    }
+
+    pub(crate) fn frame_inner(&self) -> Option<Arc<FrameInner>> {
         match self.frame_inner.upgrade() {
             Some(frame_inner) => Some(frame_inner),
             None => None,
@@ -48,6 +117,19 @@ impl Element {
             .await
     }
 
+    pub async fn query_selector_all_stream(
+        &self,
+        query: &str,
+    ) -> Result<BoxStream<'static, Result<Element>>> {
+        let frame_inner = match self.frame_inner() {
+            Some(frame_inner) => frame_inner,
+            None => return Err(anyhow!("Frame inner is not available")),
+        };
+        frame_inner
+            .query_selector_all_stream(query, Some(self.backend_node_id))
+            .await
+    }
+
     // pub async fn wait_for_selector(
     //     &self,
     //     query: &str,
@@ -85,6 +167,94 @@ impl Element {
         frame_inner.click(&self.backend_node_id).await
     }
 
+    pub async fn hover(&self) -> Result<()> {
+        let frame_inner = match self.frame_inner() {
+            Some(frame_inner) => frame_inner,
+            None => return Err(anyhow!("Frame inner is not available")),
+        };
+        frame_inner.hover(&self.backend_node_id).await
+    }
+
+    pub async fn hover_and_wait(
+        &self,
+        child_selector: &str,
+        timeout: Option<Duration>,
+    ) -> Result<Element> {
+        let frame_inner = match self.frame_inner() {
+            Some(frame_inner) => frame_inner,
+            None => return Err(anyhow!("Frame inner is not available")),
+        };
+        frame_inner
+            .hover_and_wait(&self.backend_node_id, child_selector, timeout)
+            .await
+    }
+
+    ///Both elements have to be in the same frame.
+    pub async fn drag_to(
+        &self,
+        target: &Element,
+        position: Option<(f32, f32)>,
+        html5_fallback: bool,
+    ) -> Result<()> {
+        let frame_inner = match self.frame_inner() {
+            Some(frame_inner) => frame_inner,
+            None => return Err(anyhow!("Frame inner is not available")),
+        };
+        if !Weak::ptr_eq(&self.frame_inner, &target.frame_inner) {
+            return Err(anyhow!("Drag target is in another frame"));
+        }
+        frame_inner
+            .drag_to(
+                &self.backend_node_id,
+                &target.backend_node_id,
+                position,
+                html5_fallback,
+            )
+            .await
+    }
+
+    pub async fn wait_for_stable(&self, timeout: Option<Duration>) -> Result<()> {
+        let frame_inner = match self.frame_inner() {
+            Some(frame_inner) => frame_inner,
+            None => return Err(anyhow!("Frame inner is not available")),
+        };
+        frame_inner
+            .wait_for_stable(&self.backend_node_id, timeout)
+            .await
+    }
+
+    pub async fn matches(&self, selector: &str) -> Result<bool> {
+        let frame_inner = match self.frame_inner() {
+            Some(frame_inner) => frame_inner,
+            None => return Err(anyhow!("Frame inner is not available")),
+        };
+        frame_inner.matches(&self.backend_node_id, selector).await
+    }
+
+    pub async fn check_visibility(&self, options: VisibilityOptions) -> Result<bool> {
+        let frame_inner = match self.frame_inner() {
+            Some(frame_inner) => frame_inner,
+            None => return Err(anyhow!("Frame inner is not available")),
+        };
+        frame_inner
+            .check_visibility(&self.backend_node_id, options)
+            .await
+    }
+
+    ///Elements from different frames never point to the same node.
+    pub async fn is_same_node(&self, other: &Element) -> Result<bool> {
+        let frame_inner = match self.frame_inner() {
+            Some(frame_inner) => frame_inner,
+            None => return Err(anyhow!("Frame inner is not available")),
+        };
+        if !Weak::ptr_eq(&self.frame_inner, &other.frame_inner) {
+            return Ok(false);
+        }
+        frame_inner
+            .is_same_node(&self.backend_node_id, &other.backend_node_id)
+            .await
+    }
+
     pub async fn attributes(&self) -> Result<HashMap<String, String>> {
         let frame_inner = match self.frame_inner() {
             Some(frame_inner) => frame_inner,
@@ -101,6 +271,51 @@ impl Element {
         frame_inner.get_text(&self.backend_node_id).await
     }
 
+    pub async fn inner_text(&self) -> Result<String> {
+        let frame_inner = match self.frame_inner() {
+            Some(frame_inner) => frame_inner,
+            None => return Err(anyhow!("Frame inner is not available")),
+        };
+        frame_inner.get_inner_text(&self.backend_node_id).await
+    }
+
+    pub async fn wait_for_text<F>(&self, predicate: F, timeout: Option<Duration>) -> Result<String>
+    where
+        F: Fn(&str) -> bool + Send + 'static,
+    {
+        let frame_inner = match self.frame_inner() {
+            Some(frame_inner) => frame_inner,
+            None => return Err(anyhow!("Frame inner is not available")),
+        };
+        frame_inner
+            .wait_for_text(&self.backend_node_id, predicate, timeout)
+            .await
+    }
+
+    pub async fn value(&self) -> Result<String> {
+        let frame_inner = match self.frame_inner() {
+            Some(frame_inner) => frame_inner,
+            None => return Err(anyhow!("Frame inner is not available")),
+        };
+        frame_inner.get_value(&self.backend_node_id).await
+    }
+
+    pub async fn set_value(&self, value: &str) -> Result<()> {
+        let frame_inner = match self.frame_inner() {
+            Some(frame_inner) => frame_inner,
+            None => return Err(anyhow!("Frame inner is not available")),
+        };
+        frame_inner.set_value(&self.backend_node_id, value).await
+    }
+
+    pub async fn clear(&self) -> Result<()> {
+        let frame_inner = match self.frame_inner() {
+            Some(frame_inner) => frame_inner,
+            None => return Err(anyhow!("Frame inner is not available")),
+        };
+        frame_inner.clear(&self.backend_node_id).await
+    }
+
     pub async fn type_text(&self, text: &str, delay: Option<u64>) -> Result<()> {
         let frame_inner = match self.frame_inner() {
             Some(frame_inner) => frame_inner,
@@ -133,18 +348,59 @@ impl Element {
         quality: Option<u64>,
         full_page: Option<bool>,
     ) -> Result<String> {
+        self.screenshot_with(ScreenshotOptions {
+            save_path,
+            format,
+            quality,
+            full_page,
+            hide_caret: false,
+        })
+        .await
+    }
+
+    ///Same frame limitation as `screenshot`.
+    pub async fn screenshot_with(&self, options: ScreenshotOptions<'_>) -> Result<String> {
         let frame_inner = match self.frame_inner() {
             Some(frame_inner) => frame_inner,
             None => return Err(anyhow!("Frame inner is not available")),
         };
         frame_inner
-            .screenshot(
-                save_path,
-                Some(&self.backend_node_id),
-                format,
-                quality,
-                full_page,
-            )
+            .screenshot(Some(&self.backend_node_id), options)
             .await
     }
+
+    ///Same frame limitation as `screenshot`, the whole top document is printed with the element left visible.
+    pub async fn print_to_pdf<'a>(
+        &self,
+        save_path: Option<&str>,
+        options: Option<PrintToPDF<'a>>,
+    ) -> Result<String> {
+        let frame_inner = match self.frame_inner() {
+            Some(frame_inner) => frame_inner,
+            None => return Err(anyhow!("Frame inner is not available")),
+        };
+        frame_inner
+            .print_element_to_pdf(&self.backend_node_id, save_path, options)
+            .await
+    }
+}
+
+impl fmt::Display for Element {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.description.get() {
+            Some(description) => f.write_str(description),
+            None => write!(f, "<node {}>", self.backend_node_id),
+        }
+    }
+}
+
+impl fmt::Debug for Element {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("Element");
+        debug.field("backend_node_id", &self.backend_node_id);
+        if let Some(description) = self.description.get() {
+            debug.field("description", description);
+        }
+        debug.finish()
+    }
 }
@@ -1,6 +1,9 @@
 use super::connection::Connection;
 use super::domains::browser::{GetVersion, GetVersionResponse};
-use super::domains::emulation::{SetUserAgentOverride, UserAgentMetadata};
+use super::domains::emulation::{
+    SetDeviceMetricsOverride, SetEmulatedMedia, SetGeolocationOverride, SetLocaleOverride,
+    SetScriptExecutionDisabled, SetTimezoneOverride, SetUserAgentOverride, UserAgentMetadata,
+};
 use super::domains::target::SessionId;
 use anyhow::{Result, anyhow};
 use serde::Serialize;
@@ -64,12 +67,217 @@ impl UserAgentOverride {
     }
 }
 
+///Viewport override. Zero width, height or device scale factor keeps the browser's own value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DeviceMetrics {
+    pub width: u32,
+    pub height: u32,
+    pub device_scale_factor: f64,
+    pub mobile: bool,
+}
+
+impl DeviceMetrics {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            device_scale_factor: 0.0,
+            mobile: false,
+        }
+    }
+
+    pub fn device_scale_factor(mut self, device_scale_factor: f64) -> Self {
+        self.device_scale_factor = device_scale_factor;
+        self
+    }
+
+    pub fn mobile(mut self, mobile: bool) -> Self {
+        self.mobile = mobile;
+        self
+    }
+
+    pub fn build(self) -> Self {
+        self
+    }
+
+    fn to_params(self) -> SetDeviceMetricsOverride {
+        SetDeviceMetricsOverride::default(
+            self.width,
+            self.height,
+            self.device_scale_factor,
+            self.mobile,
+        )
+    }
+}
+
+///Position reported by `navigator.geolocation`, accuracy is in meters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Geolocation {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub accuracy: f64,
+}
+
+impl Geolocation {
+    pub fn new(latitude: f64, longitude: f64) -> Self {
+        Self {
+            latitude,
+            longitude,
+            accuracy: 100.0,
+        }
+    }
+
+    pub fn accuracy(mut self, accuracy: f64) -> Self {
+        self.accuracy = accuracy;
+        self
+    }
+
+    pub fn build(self) -> Self {
+        self
+    }
+
+    fn to_params(self) -> SetGeolocationOverride {
+        SetGeolocationOverride::default(self.latitude, self.longitude, self.accuracy)
+    }
+}
+
+///Coherent geolocation, timezone and locale of a country, see `EmulationManager::emulate_country`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Country {
+    UnitedStates,
+    Canada,
+    Mexico,
+    Brazil,
+    UnitedKingdom,
+    Germany,
+    France,
+    Spain,
+    Italy,
+    Netherlands,
+    Poland,
+    Japan,
+    China,
+    SouthKorea,
+    India,
+    Australia,
+}
+
+impl Country {
+    ///Coordinates of the capital.
+    pub fn geolocation(&self) -> Geolocation {
+        let (latitude, longitude) = match self {
+            Self::UnitedStates => (38.9072, -77.0369),
+            Self::Canada => (45.4215, -75.6972),
+            Self::Mexico => (19.4326, -99.1332),
+            Self::Brazil => (-15.7939, -47.8828),
+            Self::UnitedKingdom => (51.5074, -0.1278),
+            Self::Germany => (52.5200, 13.4050),
+            Self::France => (48.8566, 2.3522),
+            Self::Spain => (40.4168, -3.7038),
+            Self::Italy => (41.9028, 12.4964),
+            Self::Netherlands => (52.3676, 4.9041),
+            Self::Poland => (52.2297, 21.0122),
+            Self::Japan => (35.6762, 139.6503),
+            Self::China => (39.9042, 116.4074),
+            Self::SouthKorea => (37.5665, 126.9780),
+            Self::India => (28.6139, 77.2090),
+            Self::Australia => (-35.2809, 149.1300),
+        };
+        Geolocation::new(latitude, longitude)
+    }
+
+    ///IANA timezone of the capital.
+    pub fn timezone(&self) -> &'static str {
+        match self {
+            Self::UnitedStates => "America/New_York",
+            Self::Canada => "America/Toronto",
+            Self::Mexico => "America/Mexico_City",
+            Self::Brazil => "America/Sao_Paulo",
+            Self::UnitedKingdom => "Europe/London",
+            Self::Germany => "Europe/Berlin",
+            Self::France => "Europe/Paris",
+            Self::Spain => "Europe/Madrid",
+            Self::Italy => "Europe/Rome",
+            Self::Netherlands => "Europe/Amsterdam",
+            Self::Poland => "Europe/Warsaw",
+            Self::Japan => "Asia/Tokyo",
+            Self::China => "Asia/Shanghai",
+            Self::SouthKorea => "Asia/Seoul",
+            Self::India => "Asia/Kolkata",
+            Self::Australia => "Australia/Sydney",
+        }
+    }
+
+    pub fn locale(&self) -> &'static str {
+        match self {
+            Self::UnitedStates => "en-US",
+            Self::Canada => "en-CA",
+            Self::Mexico => "es-MX",
+            Self::Brazil => "pt-BR",
+            Self::UnitedKingdom => "en-GB",
+            Self::Germany => "de-DE",
+            Self::France => "fr-FR",
+            Self::Spain => "es-ES",
+            Self::Italy => "it-IT",
+            Self::Netherlands => "nl-NL",
+            Self::Poland => "pl-PL",
+            Self::Japan => "ja-JP",
+            Self::China => "zh-CN",
+            Self::SouthKorea => "ko-KR",
+            Self::India => "en-IN",
+            Self::Australia => "en-AU",
+        }
+    }
+}
+
+///Restores the media emulated before `with_media`. Dropping restores it in a spawned task, so it's
+///applied eventually, `restore` waits until it is.
+#[derive(Debug)]
+pub struct MediaEmulationGuard {
+    emulation_manager: Weak<EmulationManager>,
+    previous: Option<String>,
+    restored: bool,
+}
+
+impl MediaEmulationGuard {
+    pub async fn restore(mut self) -> Result<()> {
+        self.restored = true;
+        match self.emulation_manager.upgrade() {
+            Some(emulation_manager) => emulation_manager.set_media(self.previous.take()).await,
+            None => Err(anyhow!("Emulation manager is not available")),
+        }
+    }
+}
+
+impl Drop for MediaEmulationGuard {
+    fn drop(&mut self) {
+        if self.restored {
+            return;
+        }
+        let emulation_manager = self.emulation_manager.clone();
+        let previous = self.previous.take();
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            handle.spawn(async move {
+                if let Some(emulation_manager) = emulation_manager.upgrade() {
+                    let _ = emulation_manager.set_media(previous).await;
+                }
+            });
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct EmulationManager {
     connection: Weak<Connection>,
     session_ids: DashSet<Arc<SessionId>>,
     is_default_user_agent: Arc<AtomicBool>,
     user_agent: Arc<RwLock<Option<UserAgentOverride>>>,
+    device_metrics: Arc<RwLock<Option<DeviceMetrics>>>,
+    media: Arc<RwLock<Option<String>>>,
+    javascript_enabled: Arc<AtomicBool>,
+    geolocation: Arc<RwLock<Option<Geolocation>>>,
+    timezone: Arc<RwLock<Option<String>>>,
+    locale: Arc<RwLock<Option<String>>>,
 }
 
 impl EmulationManager {
@@ -79,6 +287,12 @@ impl EmulationManager {
             session_ids: DashSet::with_capacity(4),
             is_default_user_agent: Arc::new(AtomicBool::new(true)),
             user_agent: Arc::new(RwLock::new(None)),
+            device_metrics: Arc::new(RwLock::new(None)),
+            media: Arc::new(RwLock::new(None)),
+            javascript_enabled: Arc::new(AtomicBool::new(true)),
+            geolocation: Arc::new(RwLock::new(None)),
+            timezone: Arc::new(RwLock::new(None)),
+            locale: Arc::new(RwLock::new(None)),
         })
     }
 
@@ -128,6 +342,65 @@ impl EmulationManager {
             .await?;
         }
 
+        let device_metrics = *self.device_metrics.read().await;
+        if let Some(device_metrics) = device_metrics {
+            self.send(
+                "Emulation.setDeviceMetricsOverride",
+                &device_metrics.to_params(),
+                &session_id,
+            )
+            .await?;
+        }
+
+        let media = self.media.read().await.clone();
+        if let Some(media) = media {
+            self.send(
+                "Emulation.setEmulatedMedia",
+                &SetEmulatedMedia::default(&media),
+                &session_id,
+            )
+            .await?;
+        }
+
+        if !self.javascript_enabled() {
+            self.send(
+                "Emulation.setScriptExecutionDisabled",
+                &SetScriptExecutionDisabled::default(true),
+                &session_id,
+            )
+            .await?;
+        }
+
+        let geolocation = *self.geolocation.read().await;
+        if let Some(geolocation) = geolocation {
+            self.send(
+                "Emulation.setGeolocationOverride",
+                &geolocation.to_params(),
+                &session_id,
+            )
+            .await?;
+        }
+
+        let timezone = self.timezone.read().await.clone();
+        if let Some(timezone) = timezone {
+            self.send(
+                "Emulation.setTimezoneOverride",
+                &SetTimezoneOverride::default(&timezone),
+                &session_id,
+            )
+            .await?;
+        }
+
+        let locale = self.locale.read().await.clone();
+        if let Some(locale) = locale {
+            self.send(
+                "Emulation.setLocaleOverride",
+                &SetLocaleOverride::default(Some(&locale)),
+                &session_id,
+            )
+            .await?;
+        }
+
         Ok(())
     }
 
@@ -178,4 +451,281 @@ impl EmulationManager {
         }
         Ok(())
     }
+
+    pub async fn device_metrics(&self) -> Option<DeviceMetrics> {
+        *self.device_metrics.read().await
+    }
+
+    pub async fn set_device_metrics(&self, device_metrics: DeviceMetrics) -> Result<()> {
+        let mut mut_device_metrics = self.device_metrics.write().await;
+        *mut_device_metrics = Some(device_metrics);
+
+        for session_id in self.session_ids.iter() {
+            self.send(
+                "Emulation.setDeviceMetricsOverride",
+                &device_metrics.to_params(),
+                &session_id,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    pub async fn media(&self) -> Option<String> {
+        self.media.read().await.clone()
+    }
+
+    ///None resets the media type to the browser's own.
+    pub async fn set_media(&self, media: Option<String>) -> Result<()> {
+        let mut mut_media = self.media.write().await;
+        *mut_media = media.clone();
+
+        let media = media.unwrap_or_default();
+        for session_id in self.session_ids.iter() {
+            self.send(
+                "Emulation.setEmulatedMedia",
+                &SetEmulatedMedia::default(&media),
+                &session_id,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    pub fn javascript_enabled(&self) -> bool {
+        self.javascript_enabled.load(Ordering::SeqCst)
+    }
+
+    ///Scripts of the current document keep running, the change applies from the next navigation.
+    pub async fn set_javascript_enabled(&self, enabled: bool) -> Result<()> {
+        self.javascript_enabled.store(enabled, Ordering::SeqCst);
+        for session_id in self.session_ids.iter() {
+            self.send(
+                "Emulation.setScriptExecutionDisabled",
+                &SetScriptExecutionDisabled::default(!enabled),
+                &session_id,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    pub async fn geolocation(&self) -> Option<Geolocation> {
+        *self.geolocation.read().await
+    }
+
+    ///None clears the override. Pages still need the geolocation permission to read the position.
+    pub async fn set_geolocation(&self, geolocation: Option<Geolocation>) -> Result<()> {
+        let mut mut_geolocation = self.geolocation.write().await;
+        *mut_geolocation = geolocation;
+
+        let params = match geolocation {
+            Some(geolocation) => geolocation.to_params(),
+            None => SetGeolocationOverride::clear(),
+        };
+        for session_id in self.session_ids.iter() {
+            self.send("Emulation.setGeolocationOverride", &params, &session_id)
+                .await?;
+        }
+        Ok(())
+    }
+
+    pub async fn timezone(&self) -> Option<String> {
+        self.timezone.read().await.clone()
+    }
+
+    ///IANA timezone id like `Europe/Berlin`, None restores the system timezone.
+    pub async fn set_timezone(&self, timezone: Option<String>) -> Result<()> {
+        let mut mut_timezone = self.timezone.write().await;
+        *mut_timezone = timezone.clone();
+
+        let timezone = timezone.unwrap_or_default();
+        for session_id in self.session_ids.iter() {
+            self.send(
+                "Emulation.setTimezoneOverride",
+                &SetTimezoneOverride::default(&timezone),
+                &session_id,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    pub async fn locale(&self) -> Option<String> {
+        self.locale.read().await.clone()
+    }
+
+    ///ICU locale like `de-DE`, used by `Intl` formatting. None restores the system locale.
+    pub async fn set_locale(&self, locale: Option<String>) -> Result<()> {
+        let mut mut_locale = self.locale.write().await;
+        *mut_locale = locale.clone();
+
+        for session_id in self.session_ids.iter() {
+            self.send(
+                "Emulation.setLocaleOverride",
+                &SetLocaleOverride::default(locale.as_deref()),
+                &session_id,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    ///Sets geolocation, timezone and locale of `country` together, so they never contradict each other.
+    pub async fn emulate_country(&self, country: Country) -> Result<()> {
+        self.set_geolocation(Some(country.geolocation())).await?;
+        self.set_timezone(Some(country.timezone().to_string()))
+            .await?;
+        self.set_locale(Some(country.locale().to_string())).await
+    }
+
+    pub async fn with_media(self: &Arc<Self>, media: &str) -> Result<MediaEmulationGuard> {
+        let previous = self.media().await;
+        self.set_media(Some(media.to_string())).await?;
+        Ok(MediaEmulationGuard {
+            emulation_manager: Arc::downgrade(self),
+            previous,
+            restored: false,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::mock::{MockBrowser, eventually};
+    use super::*;
+    use serde_json::{Value, json};
+
+    #[tokio::test]
+    async fn applies_device_metrics_to_new_sessions() {
+        let browser = MockBrowser::start(|_, _, _| Ok(json!({}))).await;
+        let connection = browser.connect().await;
+        let emulation_manager = EmulationManager::new(Arc::downgrade(&connection));
+
+        let device_metrics = DeviceMetrics::new(390, 844)
+            .device_scale_factor(3.0)
+            .mobile(true)
+            .build();
+        emulation_manager
+            .set_device_metrics(device_metrics)
+            .await
+            .unwrap();
+        emulation_manager
+            .add_session(Arc::new("session-PAGE".to_string()))
+            .await
+            .unwrap();
+
+        let commands = browser.commands("Emulation.setDeviceMetricsOverride");
+        assert_eq!(commands.len(), 1);
+        assert_eq!(commands[0].session_id.as_deref(), Some("session-PAGE"));
+        assert_eq!(
+            commands[0].params,
+            json!({ "width": 390, "height": 844, "deviceScaleFactor": 3.0, "mobile": true })
+        );
+        assert_eq!(
+            emulation_manager.device_metrics().await,
+            Some(device_metrics)
+        );
+    }
+
+    #[tokio::test]
+    async fn media_guard_restores_previous_media() {
+        let browser = MockBrowser::start(|_, _, _| Ok(json!({}))).await;
+        let connection = browser.connect().await;
+        let emulation_manager = EmulationManager::new(Arc::downgrade(&connection));
+        emulation_manager
+            .add_session(Arc::new("session-PAGE".to_string()))
+            .await
+            .unwrap();
+        emulation_manager
+            .set_media(Some("screen".to_string()))
+            .await
+            .unwrap();
+
+        let guard = emulation_manager.with_media("print").await.unwrap();
+        assert_eq!(emulation_manager.media().await.as_deref(), Some("print"));
+        drop(guard);
+        eventually(|| async { emulation_manager.media().await.as_deref() == Some("screen") }).await;
+
+        let media: Vec<Value> = browser
+            .commands("Emulation.setEmulatedMedia")
+            .into_iter()
+            .map(|command| command.params["media"].clone())
+            .collect();
+        assert_eq!(
+            media,
+            vec![json!("screen"), json!("print"), json!("screen")]
+        );
+
+        let guard = emulation_manager.with_media("print").await.unwrap();
+        guard.restore().await.unwrap();
+        assert_eq!(emulation_manager.media().await.as_deref(), Some("screen"));
+        tokio::task::yield_now().await;
+        assert_eq!(browser.commands("Emulation.setEmulatedMedia").len(), 5);
+    }
+
+    #[tokio::test]
+    async fn disabled_javascript_applies_to_new_sessions() {
+        let browser = MockBrowser::start(|_, _, _| Ok(json!({}))).await;
+        let connection = browser.connect().await;
+        let emulation_manager = EmulationManager::new(Arc::downgrade(&connection));
+        emulation_manager
+            .add_session(Arc::new("session-PAGE".to_string()))
+            .await
+            .unwrap();
+        assert!(
+            browser
+                .commands("Emulation.setScriptExecutionDisabled")
+                .is_empty()
+        );
+
+        emulation_manager
+            .set_javascript_enabled(false)
+            .await
+            .unwrap();
+        emulation_manager
+            .add_session(Arc::new("session-IFRAME".to_string()))
+            .await
+            .unwrap();
+
+        let commands = browser.commands("Emulation.setScriptExecutionDisabled");
+        assert_eq!(commands.len(), 2);
+        assert_eq!(commands[1].session_id.as_deref(), Some("session-IFRAME"));
+        assert_eq!(commands[1].params, json!({ "value": true }));
+        assert!(!emulation_manager.javascript_enabled());
+    }
+
+    #[tokio::test]
+    async fn country_preset_applies_to_new_sessions() {
+        let browser = MockBrowser::start(|_, _, _| Ok(json!({}))).await;
+        let connection = browser.connect().await;
+        let emulation_manager = EmulationManager::new(Arc::downgrade(&connection));
+
+        emulation_manager
+            .emulate_country(Country::Germany)
+            .await
+            .unwrap();
+        emulation_manager
+            .add_session(Arc::new("session-PAGE".to_string()))
+            .await
+            .unwrap();
+
+        let geolocation = browser.commands("Emulation.setGeolocationOverride");
+        assert_eq!(geolocation.len(), 1);
+        assert_eq!(geolocation[0].params["latitude"], 52.52);
+        assert_eq!(
+            browser.commands("Emulation.setTimezoneOverride")[0].params,
+            json!({ "timezoneId": "Europe/Berlin" })
+        );
+        assert_eq!(
+            browser.commands("Emulation.setLocaleOverride")[0].params,
+            json!({ "locale": "de-DE" })
+        );
+
+        emulation_manager.set_locale(None).await.unwrap();
+        assert_eq!(
+            browser.commands("Emulation.setLocaleOverride")[1].params,
+            json!({})
+        );
+    }
 }
@@ -1,48 +1,290 @@
-use super::connection::{Event, EventParams, Response};
+use super::connection::{EventParams, EventReceiver, Response};
+use super::console::{ConsoleStream, PageErrorStream};
+use super::cookie_jar::parse_cookies;
+use super::domains::browser::{DownloadBehavior, SetDownloadBehavior};
 use super::domains::dom::{
     BackendNodeId, DescribeNode, DescribeNodeResponse, DescribeNodeResponseFull, Focus,
     GetAttributes, GetAttributesResponse, GetBoxModel, GetBoxModelResponse, GetDocument,
     GetDocumentResponse, MinimalNode, NodeId, PushNodesByBackendIdsToFrontend,
-    PushNodesByBackendIdsToFrontendResponse, ScrollIntoViewIfNeeded,
+    PushNodesByBackendIdsToFrontendResponse, ResolveNode, ResolveNodeResponse,
+    ScrollIntoViewIfNeeded,
 };
 use super::domains::dom_storage::{
     DomStorageDisable, DomStorageEnable, GetDOMStorageItems, GetDOMStorageItemsResponse, Item,
     RemoveDOMStorageItem, SerializedStorageKey, StorageId,
 };
 use super::domains::input::{
-    DispatchKeyEvent, DispatchMouseEvent, KeyEventType, MouseButton, MouseEventType,
+    DispatchKeyEvent, DispatchMouseEvent, GestureSourceType, KeyEventType, MouseButton,
+    MouseEventType, SynthesizeScrollGesture,
 };
+use super::domains::network::SetCookies;
 use super::domains::page::{
     AddScriptToEvaluateOnNewDocument, AddScriptToEvaluateOnNewDocumentResponse, CaptureScreenshot,
-    CaptureScreenshotResponse, FrameId, GetNavigationHistory, GetNavigationHistoryResponse,
+    CaptureScreenshotResponse, CreateIsolatedWorld, CreateIsolatedWorldResponse, FrameId,
+    GetLayoutMetrics, GetLayoutMetricsResponse, GetNavigationHistory, GetNavigationHistoryResponse,
     Navigate, PrintToPDF, PrintToPDFResponse, Reload, RemoveScriptToEvaluateOnNewDocument,
-    ScriptIdentifier, Viewport,
+    ScriptIdentifier, SetDocumentContent, Viewport,
+};
+use super::domains::runtime::{
+    CallArgument, CallFunctionOn, CallFunctionOnResponse, Evaluate, EvaluateResponse,
+    GetProperties, GetPropertiesResponse, ReleaseObject, ReleaseObjectGroup, RemoteObjectId,
 };
 use super::domains::target::{ActivateTarget, CloseTarget};
-use super::element::Element;
-use super::emulation_manager::{EmulationManager, UserAgentOverride};
+use super::domains::web_authn::{
+    AddCredential, AddVirtualAuthenticator, AddVirtualAuthenticatorResponse, AuthenticatorId,
+    Credential, GetCredentials, GetCredentialsResponse, RemoveVirtualAuthenticator,
+    VirtualAuthenticatorOptions, WebAuthnEnable,
+};
+use super::element::{Element, VisibilityOptions};
+use super::emulation_manager::{
+    Country, DeviceMetrics, EmulationManager, Geolocation, MediaEmulationGuard, UserAgentOverride,
+};
 use super::file_chooser::FileChooser;
+use super::http_request::FailedRequest;
 use super::http_response::HttpResponse;
+use super::input_recorder::InputRecording;
 use super::js_dialogs::JsDialog;
 use super::js_manager::JsManager;
 use super::network_manager::{NetworkManager, RequestStream, ResponseStream};
-use super::query_builder::QueryBuilder;
+use super::page::ScreenshotOptions;
+use super::query_builder::{QueryBuilder, is_plain_css};
 use super::target::Target;
-use super::target_manager::TargetManager;
+use super::target_manager::{TargetEvent, TargetManager};
+use super::web_vitals::{READ_WEB_VITALS_JS, WEB_VITALS_JS, WebVitals};
 use anyhow::{Result, anyhow};
 use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
 use dashmap::{DashMap, DashSet};
 use futures::future::join_all;
+use futures::stream::{self, BoxStream, StreamExt};
+use regex::Regex;
 use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
 use std::collections::HashMap;
 
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::{Arc, Weak};
 use tokio::join;
-use tokio::sync::{Mutex, RwLock, broadcast, mpsc, oneshot};
+use tokio::sync::{Mutex, RwLock, broadcast, oneshot};
 use tokio::task::JoinHandle;
 use tokio::time::Duration;
 
+//How long pointer actions wait for a moving element, ones that animate forever are clicked anyway
+const ACTIONABILITY_STABLE_TIMEOUT: Duration = Duration::from_millis(500);
+
+//Hidden documents don't run animation frames, fall back to timers there.
+const WAIT_FOR_STABLE_JS: &str = r#"function() {
+    if (!this.isConnected) {
+        throw new Error('Element is detached from the document');
+    }
+    const rect = () => {
+        const r = this.getBoundingClientRect();
+        return [r.x, r.y, r.width, r.height];
+    };
+    const frame = () => new Promise((resolve) => {
+        if (document.visibilityState === 'hidden') {
+            setTimeout(resolve, 16);
+        } else {
+            requestAnimationFrame(() => resolve());
+        }
+    });
+    return (async () => {
+        const first = rect();
+        await frame();
+        await frame();
+        const second = rect();
+        return first.every((value, index) => value === second[index]);
+    })();
+}"#;
+
+const SET_VALUE_JS: &str = r#"function(value) {
+    if (!('value' in this)) {
+        throw new Error('Element has no value property');
+    }
+    const descriptor = Object.getOwnPropertyDescriptor(Object.getPrototypeOf(this), 'value');
+    if (descriptor && descriptor.set) {
+        descriptor.set.call(this, value);
+    } else {
+        this.value = value;
+    }
+    this.dispatchEvent(new Event('input', { bubbles: true }));
+    this.dispatchEvent(new Event('change', { bubbles: true }));
+}"#;
+
+const CLEAR_JS: &str = r#"function() {
+    this.focus();
+    if (this.isContentEditable) {
+        this.textContent = '';
+        this.dispatchEvent(new InputEvent('input', { bubbles: true, inputType: 'deleteContentBackward' }));
+        return;
+    }
+    if (!('value' in this)) {
+        throw new Error('Element is neither a form control nor contenteditable');
+    }
+    const descriptor = Object.getOwnPropertyDescriptor(Object.getPrototypeOf(this), 'value');
+    if (descriptor && descriptor.set) {
+        descriptor.set.call(this, '');
+    } else {
+        this.value = '';
+    }
+    this.dispatchEvent(new Event('input', { bubbles: true }));
+    this.dispatchEvent(new Event('change', { bubbles: true }));
+}"#;
+
+const HIDE_CARET_JS: &str = r#"(() => {
+    const style = document.createElement('style');
+    style.id = '__chromatica_hide_caret';
+    style.textContent = '* { caret-color: transparent !important; }';
+    (document.head || document.documentElement).appendChild(style);
+})()"#;
+
+const SHOW_CARET_JS: &str = "document.getElementById('__chromatica_hide_caret')?.remove()";
+
+const DRAG_STEPS: usize = 10;
+
+const WATCH_DROP_JS: &str = r#"function() {
+    this.__chromatica_dropped = false;
+    this.addEventListener('drop', () => { this.__chromatica_dropped = true; }, { once: true, capture: true });
+}"#;
+
+//Drop goes to the deepest node under the point, as a native drop would, if it's inside the target.
+const HTML5_DRAG_JS: &str = r#"function(target, clientX, clientY) {
+    const dataTransfer = new DataTransfer();
+    const source = this.getBoundingClientRect();
+    const fire = (node, type, x, y) => node.dispatchEvent(new DragEvent(type, {
+        bubbles: true, cancelable: true, composed: true, dataTransfer, clientX: x, clientY: y,
+    }));
+    const hit = document.elementFromPoint(clientX, clientY);
+    const dropTarget = hit && target.contains(hit) ? hit : target;
+    fire(this, 'dragstart', source.left + source.width / 2, source.top + source.height / 2);
+    fire(dropTarget, 'dragenter', clientX, clientY);
+    fire(dropTarget, 'dragover', clientX, clientY);
+    fire(dropTarget, 'drop', clientX, clientY);
+    fire(this, 'dragend', clientX, clientY);
+}"#;
+
+//Marks the element and its ancestors, print media then hides every unmarked sibling along the path.
+const ISOLATE_FOR_PRINT_JS: &str = r#"function() {
+    this.setAttribute('data-chromatica-print', 'target');
+    for (let node = this.parentElement; node; node = node.parentElement) {
+        node.setAttribute('data-chromatica-print', 'ancestor');
+    }
+    const style = document.createElement('style');
+    style.id = '__chromatica_print_element';
+    style.textContent = '@media print { [data-chromatica-print="ancestor"] > :not([data-chromatica-print]) { display: none !important; } }';
+    (document.head || document.documentElement).appendChild(style);
+}"#;
+
+const RESTORE_AFTER_PRINT_JS: &str = r#"(() => {
+    document.getElementById('__chromatica_print_element')?.remove();
+    for (const node of document.querySelectorAll('[data-chromatica-print]')) {
+        node.removeAttribute('data-chromatica-print');
+    }
+})()"#;
+
+const QUERY_ALL_JS: &str =
+    "function(selector) { return Array.from(this.querySelectorAll(selector)); }";
+
+static NEXT_OBJECT_GROUP: AtomicUsize = AtomicUsize::new(0);
+
+//Lazy queries keep their matches as remote objects, the whole group is released once the stream is gone.
+struct ObjectGroupGuard {
+    frame_inner: Weak<FrameInner>,
+    object_group: String,
+}
+
+impl Drop for ObjectGroupGuard {
+    fn drop(&mut self) {
+        let Some(frame_inner) = self.frame_inner.upgrade() else {
+            return;
+        };
+        let object_group = std::mem::take(&mut self.object_group);
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            handle.spawn(async move {
+                let _ = frame_inner
+                    .send(
+                        "Runtime.releaseObjectGroup",
+                        &ReleaseObjectGroup::default(&object_group),
+                    )
+                    .await;
+            });
+        }
+    }
+}
+
+//Requests in flight at once for batched reads, so a few hundred elements don't flood the socket.
+const ATTRIBUTES_BATCH_CONCURRENCY: usize = 32;
+
+//DOM.getAttributes returns a flat [name, value, name, value, ...] list.
+fn attributes_to_map(attributes: Vec<String>) -> HashMap<String, String> {
+    let mut result = HashMap::new();
+    for chunk in attributes.chunks(2) {
+        if chunk.len() == 2 {
+            result.insert(chunk[0].clone(), chunk[1].clone());
+        }
+    }
+    result
+}
+
+//Falls back to a box and computed style check in browsers older than checkVisibility (Chrome 105).
+const CHECK_VISIBILITY_JS: &str = r#"function(options) {
+    const element = this.nodeType === Node.ELEMENT_NODE ? this : this.parentElement;
+    if (!element) return false;
+    if (typeof element.checkVisibility === 'function') return element.checkVisibility(options);
+    if (!element.getClientRects().length) return false;
+    const style = getComputedStyle(element);
+    if (options.checkVisibilityCSS && style.visibility !== 'visible') return false;
+    if (options.checkOpacity && style.opacity === '0') return false;
+    return true;
+}"#;
+
+//Puts <base href> first in <head>, so it applies to every relative url of the document.
+fn with_base_url(html: &str, base_url: &str) -> String {
+    let base_url = base_url.replace('&', "&amp;").replace('"', "&quot;");
+    let base = format!("<base href=\"{}\">", base_url);
+    let head = Regex::new(r"(?i)<head(\s[^>]*)?>").unwrap();
+    match head.find(html) {
+        Some(head) => format!("{}{}{}", &html[..head.end()], base, &html[head.end()..]),
+        None => format!("{}{}", base, html),
+    }
+}
+
+//Creates missing parent directories, otherwise write fails with bare "No such file or directory".
+async fn save_file(path: &str, data: &[u8]) -> Result<()> {
+    if let Some(parent) = std::path::Path::new(path).parent()
+        && !parent.as_os_str().is_empty()
+    {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    tokio::fs::write(path, data).await?;
+    Ok(())
+}
+
+async fn next_matching_response<F>(
+    responses: &mut broadcast::Receiver<HttpResponse>,
+    predicate: F,
+) -> Result<HttpResponse>
+where
+    F: Fn(&HttpResponse) -> bool,
+{
+    loop {
+        match responses.recv().await {
+            Ok(response) => {
+                if predicate(&response) {
+                    return Ok(response);
+                }
+            }
+            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(broadcast::error::RecvError::Closed) => break,
+        }
+        tokio::task::yield_now().await;
+    }
+    Err(anyhow!(
+        "Response stream closed before a matching response arrived, target is probably destroyed"
+    ))
+}
+
 #[derive(Debug, Clone)]
 pub struct FrameInner {
     target: Arc<RwLock<Weak<Target>>>,
@@ -50,7 +292,9 @@ pub struct FrameInner {
     parent_frame_id: Option<Arc<FrameId>>,
     child_frame_ids: DashSet<Arc<FrameId>>,
     backend_node_id: Arc<RwLock<Option<BackendNodeId>>>,
+    document_root: Arc<RwLock<Option<BackendNodeId>>>,
     default_timeout: Arc<RwLock<Duration>>,
+    web_vitals_installed: Arc<AtomicBool>,
 }
 
 impl FrameInner {
@@ -60,13 +304,20 @@ impl FrameInner {
         parent_frame_id: Option<Arc<FrameId>>,
         backend_node_id: Option<BackendNodeId>,
     ) -> Self {
+        let default_timeout = target
+            .upgrade()
+            .and_then(|target| target.connection())
+            .map(|connection| connection.default_timeout())
+            .unwrap_or(Duration::from_secs(30));
         Self {
             target: Arc::new(RwLock::new(target)),
             frame_id,
             parent_frame_id,
             child_frame_ids: DashSet::with_capacity(4),
             backend_node_id: Arc::new(RwLock::new(backend_node_id)),
-            default_timeout: Arc::new(RwLock::new(Duration::from_secs(30))),
+            document_root: Arc::new(RwLock::new(None)),
+            default_timeout: Arc::new(RwLock::new(default_timeout)),
+            web_vitals_installed: Arc::new(AtomicBool::new(false)),
             // dom_lock: Arc::new(Mutex::new(())),
         }
     }
@@ -76,6 +327,12 @@ impl FrameInner {
         *current_target = target.clone();
         let mut backend_node_id = self.backend_node_id.write().await;
         *backend_node_id = None;
+        self.on_navigated().await;
+    }
+
+    ///Drops everything cached for the current document, called on `Page.frameNavigated`.
+    pub async fn on_navigated(&self) {
+        *self.document_root.write().await = None;
     }
 
     pub async fn target(&self) -> Arc<Target> {
@@ -162,10 +419,21 @@ impl FrameInner {
         target.send(method, params).await
     }
 
-    pub async fn subscribe(
-        &self,
-        methods: DashSet<String>,
-    ) -> Result<mpsc::UnboundedReceiver<Arc<Event>>> {
+    ///Same-process iframes share the session of the target they belong to.
+    pub async fn session_id(&self) -> String {
+        self.target().await.session_id().to_string()
+    }
+
+    pub async fn target_id(&self) -> String {
+        self.target().await.target_id().to_string()
+    }
+
+    pub async fn send_cdp(&self, method: &str, params: Value) -> Result<Value> {
+        let response = self.send(method, &params).await?;
+        response.result_as::<Value>()
+    }
+
+    pub async fn subscribe(&self, methods: DashSet<String>) -> Result<EventReceiver> {
         let target = self.target().await;
         target.subscribe(methods).await
     }
@@ -188,11 +456,96 @@ impl FrameInner {
         Ok((requests, responses))
     }
 
+    pub async fn subscribe_to_failed_requests(&self) -> broadcast::Receiver<FailedRequest> {
+        let network_manager = self.network_manager().await;
+        network_manager.subscribe_to_failed_requests()
+    }
+
+    pub async fn pending_request_count(&self) -> usize {
+        let network_manager = self.network_manager().await;
+        network_manager.pending_request_count()
+    }
+
+    ///Browser.setDownloadBehavior is browser wide, so it's scoped to the page's browser context.
+    pub async fn set_download_behavior(
+        &self,
+        behavior: DownloadBehavior,
+        path: Option<&str>,
+    ) -> Result<()> {
+        let target = self.target().await;
+        let Some(conn) = target.connection() else {
+            return Err(anyhow!("Connection is not available"));
+        };
+
+        //Chrome accepts only absolute download paths
+        let path = match path {
+            Some(path) => Some(std::path::absolute(path)?.to_string_lossy().into_owned()),
+            None => None,
+        };
+
+        let mut params = SetDownloadBehavior::new().behavior(behavior);
+        let browser_context_id = target.browser_context_id();
+        if browser_context_id != "default" {
+            params = params.browser_context_id(browser_context_id);
+        }
+        if let Some(path) = path.as_deref() {
+            params = params.download_path(path);
+        }
+
+        conn.send("Browser.setDownloadBehavior", &params.build(), None)
+            .await?
+            .result_as::<Value>()?;
+        Ok(())
+    }
+
+    pub async fn import_cookies(&self, jar: &str) -> Result<()> {
+        let cookies = parse_cookies(jar)?;
+        if cookies.is_empty() {
+            return Ok(());
+        }
+        //Header cookies carry no domain, Chrome derives it from the url
+        let url = match cookies
+            .iter()
+            .any(|jar_cookie| jar_cookie.cookie.domain.is_empty())
+        {
+            true => Some(self.url().await?),
+            false => None,
+        };
+        let params = cookies
+            .iter()
+            .map(|jar_cookie| {
+                let mut params = jar_cookie.cookie.to_cookie_params();
+                if jar_cookie.url.is_some() {
+                    params.url = jar_cookie.url.as_deref();
+                    params.domain = None;
+                } else if jar_cookie.cookie.domain.is_empty() {
+                    params.url = url.as_deref();
+                    params.domain = None;
+                }
+                params
+            })
+            .collect();
+        self.send("Network.setCookies", &SetCookies::default(&params))
+            .await?
+            .result_as::<Value>()?;
+        Ok(())
+    }
+
     pub async fn set_extra_headers(&self, headers: HashMap<&str, &str>) -> Result<()> {
         let network_manager = self.network_manager().await;
         network_manager.set_extra_headers(headers).await
     }
 
+    pub async fn add_extra_header(&self, name: &str, value: &str) -> Result<()> {
+        let network_manager = self.network_manager().await;
+        network_manager.add_extra_header(name, value).await
+    }
+
+    pub async fn remove_extra_header(&self, name: &str) -> Result<()> {
+        let network_manager = self.network_manager().await;
+        network_manager.remove_extra_header(name).await
+    }
+
     pub async fn clear_extra_headers(&self) -> Result<()> {
         let network_manager = self.network_manager().await;
         network_manager.clear_extra_headers().await
@@ -208,6 +561,25 @@ impl FrameInner {
         target.js_manager().unwrap()
     }
 
+    pub async fn subscribe_to_console(&self) -> Result<ConsoleStream> {
+        let target = self.target().await;
+        let methods = DashSet::with_capacity(1);
+        methods.insert("Runtime.consoleAPICalled".to_string());
+        //Subscribe before enabling, Runtime.enable replays messages logged so far.
+        let receiver = target.subscribe_stream(methods).await?;
+        let runtime = target.runtime_guard().await;
+        Ok(ConsoleStream::new(receiver, runtime))
+    }
+
+    pub async fn subscribe_to_page_errors(&self) -> Result<PageErrorStream> {
+        let target = self.target().await;
+        let methods = DashSet::with_capacity(1);
+        methods.insert("Runtime.exceptionThrown".to_string());
+        let receiver = target.subscribe_stream(methods).await?;
+        let runtime = target.runtime_guard().await;
+        Ok(PageErrorStream::new(receiver, runtime))
+    }
+
     pub async fn subscribe_to_js_dialogs(&self) -> Result<broadcast::Receiver<JsDialog>> {
         let js_manager = self.js_manager().await;
         let dialogs = js_manager.subscribe_to_js_dialogs();
@@ -261,7 +633,30 @@ impl FrameInner {
         }
     }
 
+    ///Node queries start from. The frame owner for same-process iframes, otherwise the document, cached until the next navigation.
+    pub async fn root_backend_node_id(&self) -> Result<BackendNodeId> {
+        if let Some(backend_node_id) = *self.backend_node_id.read().await {
+            return Ok(backend_node_id);
+        }
+        if let Some(document_root) = *self.document_root.read().await {
+            return Ok(document_root);
+        }
+        let document_root = self.node(0).await?.backend_node_id;
+        *self.document_root.write().await = Some(document_root);
+        Ok(document_root)
+    }
+
     pub async fn bound_node(&self, backend_node_id: &BackendNodeId) -> Result<NodeId> {
+        match self.push_node(backend_node_id).await {
+            Err(e) => match self.refresh_stale_root(backend_node_id).await {
+                Some(root) => self.push_node(&root).await,
+                None => Err(e),
+            },
+            node_id => node_id,
+        }
+    }
+
+    async fn push_node(&self, backend_node_id: &BackendNodeId) -> Result<NodeId> {
         let response = self
             .send(
                 "DOM.pushNodesByBackendIdsToFrontend",
@@ -272,9 +667,25 @@ impl FrameInner {
             .result_as::<PushNodesByBackendIdsToFrontendResponse>()?
             .node_ids[0]
             .clone();
+        //Nodes which are gone are reported as 0
+        if node_id == 0 {
+            return Err(anyhow!("Node {} is not in the document", backend_node_id));
+        }
         Ok(node_id)
     }
 
+    ///Fresh document root if `backend_node_id` is the cached one, a navigation the target manager hasn't seen yet replaced it.
+    async fn refresh_stale_root(&self, backend_node_id: &BackendNodeId) -> Option<BackendNodeId> {
+        {
+            let mut document_root = self.document_root.write().await;
+            if *document_root != Some(*backend_node_id) {
+                return None;
+            }
+            *document_root = None;
+        }
+        self.root_backend_node_id().await.ok()
+    }
+
     pub async fn backend_node_id(&self) -> BackendNodeId {
         let backend_node_id = self.backend_node_id.read().await;
         match backend_node_id.as_ref() {
@@ -339,17 +750,11 @@ impl FrameInner {
     {
         let timeout = timeout.or(Some(self.default_timeout().await)).unwrap();
         let network_manager = self.network_manager().await;
-        let mut responses = network_manager.subscribe_to_responses();
+        //Plain receiver, ResponseStream would disable interception on drop and break user's handlers.
+        let mut responses = network_manager.observe_responses();
 
-        let mut handle = tokio::spawn(async move {
-            while let Some(response) = responses.next().await {
-                if predicate(&response) {
-                    return Ok(response);
-                }
-                tokio::task::yield_now().await;
-            }
-            Err(anyhow!("No matching response found"))
-        });
+        let mut handle =
+            tokio::spawn(async move { next_matching_response(&mut responses, predicate).await });
 
         if timeout.is_zero() {
             let response = handle.await?;
@@ -362,11 +767,52 @@ impl FrameInner {
                 }
                 Err(_) => {
                     handle.abort();
-                    Err(anyhow!("Waiting for response timed out"))
+                    Err(anyhow!(
+                        "Waiting for response timed out after {:?}",
+                        timeout
+                    ))
                 }
             }
         }
     }
+
+    ///Runs `action` and waits for the response it triggers. Subscription happens before the action, so fast responses aren't missed.
+    ///Interception state isn't touched: with interception on responses would stall until user continues them.
+    pub async fn expect_response<F, Fut, T>(
+        &self,
+        predicate: F,
+        action: Fut,
+        timeout: Option<Duration>,
+    ) -> Result<HttpResponse>
+    where
+        F: Fn(&HttpResponse) -> bool + Send + 'static,
+        Fut: Future<Output = Result<T>>,
+    {
+        let timeout = timeout.unwrap_or(self.default_timeout().await);
+        let network_manager = self.network_manager().await;
+        let mut responses = network_manager.observe_responses();
+
+        let expectation = async {
+            let (_, response) = tokio::try_join!(
+                async { action.await.map(|_| ()) },
+                next_matching_response(&mut responses, &predicate),
+            )?;
+            Ok(response)
+        };
+
+        if timeout.is_zero() {
+            expectation.await
+        } else {
+            match tokio::time::timeout(timeout, expectation).await {
+                Ok(response) => response,
+                Err(_) => Err(anyhow!(
+                    "Waiting for response timed out after {:?}",
+                    timeout
+                )),
+            }
+        }
+    }
+
     pub async fn wait_for_navigation(
         &self,
         wait_until: Option<&str>,
@@ -415,6 +861,7 @@ impl FrameInner {
         let mut events = self.subscribe(methods).await?;
         let frame_id = self.frame_id();
         let (tx, rx) = oneshot::channel();
+        let awaited = wait_until.to_string();
 
         let handle = tokio::spawn(async move {
             while let Some(event) = events.recv().await {
@@ -442,10 +889,13 @@ impl FrameInner {
                 }
                 tokio::task::yield_now().await;
             }
-            let _ = tx.send(Err(anyhow!("Channel closed")));
+            let _ = tx.send(Err(anyhow!(
+                "Waiting for navigation ('{}') failed: event channel closed, target is probably destroyed",
+                awaited
+            )));
         });
 
-        if timeout.is_zero() {
+        let result = if timeout.is_zero() {
             rx.await?
         } else {
             match tokio::time::timeout(timeout, rx).await {
@@ -455,10 +905,19 @@ impl FrameInner {
                 }
                 Err(_) => {
                     handle.abort();
-                    Err(anyhow!("Navigation timed out"))
+                    Err(anyhow!(
+                        "Navigation timed out after {:?} waiting for '{}'",
+                        timeout,
+                        wait_until
+                    ))
                 }
             }
+        };
+        //The target manager clears it too, but only once it gets to `Page.frameNavigated`
+        if result.is_ok() {
+            self.on_navigated().await;
         }
+        result
     }
 
     pub async fn navigate(
@@ -486,6 +945,21 @@ impl FrameInner {
         Ok(())
     }
 
+    pub async fn set_content(&self, html: &str, base_url: Option<&str>) -> Result<()> {
+        let html = match base_url {
+            Some(base_url) => with_base_url(html, base_url),
+            None => html.to_string(),
+        };
+        self.send(
+            "Page.setDocumentContent",
+            &SetDocumentContent::default(&self.frame_id, &html),
+        )
+        .await?
+        .result_as::<Value>()?;
+        self.on_navigated().await;
+        Ok(())
+    }
+
     pub async fn reload(
         self: &Arc<Self>,
         wait_until: Option<&str>,
@@ -517,25 +991,90 @@ impl FrameInner {
         }
     }
 
+    ///Closes the target and waits until the target manager has processed `Target.targetDestroyed`.
+    pub async fn close_and_wait(&self, timeout: Option<Duration>) -> Result<()> {
+        let timeout = timeout.unwrap_or(self.default_timeout().await);
+        let target_id = self.frame_id();
+        let target_manager = self.target_manager().await;
+        //Subscribed before closing, the event can arrive before the command's response.
+        let mut target_events = target_manager.subscribe_to_targets();
+        self.close().await?;
+
+        let destroyed = async {
+            loop {
+                match target_events.recv().await {
+                    Ok(TargetEvent::Destroyed(destroyed_id)) if destroyed_id == *target_id => {
+                        return Ok(());
+                    }
+                    Ok(_) => continue,
+                    Err(broadcast::error::RecvError::Lagged(_)) => {
+                        if target_manager.get_frame_inner(&target_id).await.is_none() {
+                            return Ok(());
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Closed) => {
+                        return Err(anyhow!("Target event channel closed"));
+                    }
+                }
+            }
+        };
+        if timeout.is_zero() {
+            return destroyed.await;
+        }
+        match tokio::time::timeout(timeout, destroyed).await {
+            Ok(result) => result,
+            Err(_) => Err(anyhow!(
+                "Waiting for target '{}' to be destroyed timed out after {:?}",
+                target_id,
+                timeout
+            )),
+        }
+    }
+
     pub async fn screenshot(
         self: &Arc<Self>,
-        save_path: Option<&str>,
         backend_node_id: Option<&BackendNodeId>,
-        format: Option<&str>,
-        quality: Option<u64>,
-        full_page: Option<bool>,
+        options: ScreenshotOptions<'_>,
     ) -> Result<String> {
+        let ScreenshotOptions {
+            save_path,
+            format,
+            quality,
+            full_page,
+            hide_caret,
+        } = options;
         let mut params = CaptureScreenshot::new();
         if let Some(format) = format {
             params.format = Some(format);
         }
         if let Some(quality) = quality {
-            params.quality = Some(quality);
+            //Chrome silently ignores quality for png
+            match format {
+                Some("jpeg") | Some("webp") => params.quality = Some(quality.min(100)),
+                _ => {
+                    return Err(anyhow!(
+                        "Screenshot quality is supported only for jpeg and webp, got {} format",
+                        format.unwrap_or("png")
+                    ));
+                }
+            }
+        }
+        if let Some(full_page) = full_page {
+            params.capture_beyond_viewport = Some(full_page);
         }
         if let Some(backend_node_id) = backend_node_id {
-            let response = self
-                .send("DOM.getBoxModel", &GetBoxModel::default(backend_node_id))
-                .await?;
+            //Box model is relative to the viewport, clip has to be relative to the document
+            //and captured beyond viewport, otherwise elements taller than the viewport are cut off.
+            let get_box_model = GetBoxModel::default(backend_node_id);
+            let get_layout_metrics = GetLayoutMetrics::default();
+            let (box_model, layout_metrics) = join!(
+                self.send("DOM.getBoxModel", &get_box_model),
+                self.send("Page.getLayoutMetrics", &get_layout_metrics),
+            );
+            let viewport = layout_metrics?
+                .result_as::<GetLayoutMetricsResponse>()?
+                .css_layout_viewport;
+            let response = box_model?;
             let content = response.result_as::<GetBoxModelResponse>()?.model.content;
             let xs = [content[0], content[2], content[4], content[6]];
             let ys = [content[1], content[3], content[5], content[7]];
@@ -545,18 +1084,28 @@ impl FrameInner {
             let max_y = ys.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
             let width = max_x - min_x;
             let height = max_y - min_y;
-            params.clip = Some(Viewport::default(min_x, min_y, width, height, 1.0));
+            //Clip scale is on top of the device scale factor, 1.0 already gives images at the emulated DPR
+            params.clip = Some(Viewport::default(
+                min_x + viewport.page_x,
+                min_y + viewport.page_y,
+                width,
+                height,
+                1.0,
+            ));
+            params.capture_beyond_viewport = Some(true);
         }
 
-        if let Some(full_page) = full_page {
-            params.capture_beyond_viewport = Some(full_page);
+        if hide_caret {
+            self.evaluate_expression(HIDE_CARET_JS).await?;
         }
-
-        let response = self.send("Page.captureScreenshot", &params.build()).await?;
-        let data = response.result_as::<CaptureScreenshotResponse>()?.data;
+        let response = self.send("Page.captureScreenshot", &params.build()).await;
+        if hide_caret {
+            let _ = self.evaluate_expression(SHOW_CARET_JS).await;
+        }
+        let data = response?.result_as::<CaptureScreenshotResponse>()?.data;
         if let Some(path) = save_path {
             let data = BASE64.decode(&data)?;
-            tokio::fs::write(path, data).await?;
+            save_file(path, &data).await?;
             Ok(path.to_string())
         } else {
             Ok(data)
@@ -573,19 +1122,105 @@ impl FrameInner {
         let data = response.result_as::<PrintToPDFResponse>()?.data;
         if let Some(path) = save_path {
             let data = BASE64.decode(&data)?;
-            tokio::fs::write(path, data).await?;
+            save_file(path, &data).await?;
             Ok(path.to_string())
         } else {
             Ok(data)
         }
     }
 
+    ///Prints only the element, everything outside its ancestor chain is hidden for print media.
+    pub async fn print_element_to_pdf<'a>(
+        self: &Arc<Self>,
+        backend_node_id: &BackendNodeId,
+        save_path: Option<&str>,
+        options: Option<PrintToPDF<'a>>,
+    ) -> Result<String> {
+        let isolated = self
+            .call_function_on(backend_node_id, ISOLATE_FOR_PRINT_JS, vec![])
+            .await;
+        let result = match isolated {
+            Ok(_) => self.print_to_pdf(save_path, options).await,
+            Err(e) => Err(e),
+        };
+        let _ = self.evaluate_expression(RESTORE_AFTER_PRINT_JS).await;
+        result
+    }
+
     pub async fn set_user_agent(&self, user_agent: UserAgentOverride) -> Result<()> {
         let emulation_manager = self.emulation_manager().await;
         let _ = emulation_manager.set_user_agent(user_agent).await;
         Ok(())
     }
 
+    pub async fn set_viewport(&self, device_metrics: DeviceMetrics) -> Result<()> {
+        let emulation_manager = self.emulation_manager().await;
+        emulation_manager.set_device_metrics(device_metrics).await
+    }
+
+    pub async fn viewport(&self) -> Option<DeviceMetrics> {
+        let emulation_manager = self.emulation_manager().await;
+        emulation_manager.device_metrics().await
+    }
+
+    pub async fn emulate_media(&self, media: Option<&str>) -> Result<()> {
+        let emulation_manager = self.emulation_manager().await;
+        emulation_manager
+            .set_media(media.map(|media| media.to_string()))
+            .await
+    }
+
+    pub async fn emulated_media(&self) -> Option<String> {
+        let emulation_manager = self.emulation_manager().await;
+        emulation_manager.media().await
+    }
+
+    pub async fn set_javascript_enabled(&self, enabled: bool) -> Result<()> {
+        let emulation_manager = self.emulation_manager().await;
+        emulation_manager.set_javascript_enabled(enabled).await
+    }
+
+    pub async fn javascript_enabled(&self) -> bool {
+        let emulation_manager = self.emulation_manager().await;
+        emulation_manager.javascript_enabled()
+    }
+
+    pub async fn set_geolocation(&self, geolocation: Option<Geolocation>) -> Result<()> {
+        let emulation_manager = self.emulation_manager().await;
+        emulation_manager.set_geolocation(geolocation).await
+    }
+
+    pub async fn set_timezone(&self, timezone: Option<&str>) -> Result<()> {
+        let emulation_manager = self.emulation_manager().await;
+        emulation_manager
+            .set_timezone(timezone.map(|timezone| timezone.to_string()))
+            .await
+    }
+
+    pub async fn set_locale(&self, locale: Option<&str>) -> Result<()> {
+        let emulation_manager = self.emulation_manager().await;
+        emulation_manager
+            .set_locale(locale.map(|locale| locale.to_string()))
+            .await
+    }
+
+    pub async fn emulate_country(&self, country: Country) -> Result<()> {
+        let emulation_manager = self.emulation_manager().await;
+        emulation_manager.emulate_country(country).await
+    }
+
+    pub async fn with_media(&self, media: &str) -> Result<MediaEmulationGuard> {
+        let emulation_manager = self.emulation_manager().await;
+        emulation_manager.with_media(media).await
+    }
+
+    pub async fn device_pixel_ratio(&self) -> Result<f64> {
+        let ratio = self.evaluate_expression("devicePixelRatio").await?;
+        ratio
+            .as_f64()
+            .ok_or_else(|| anyhow!("Unexpected devicePixelRatio: {}", ratio))
+    }
+
     pub async fn user_agent(&self) -> Result<String> {
         let emulation_manager = self.emulation_manager().await;
         let user_agent = emulation_manager.user_agent().await?;
@@ -613,31 +1248,144 @@ impl FrameInner {
         Ok(Element::new(Arc::downgrade(&frame_inner), backend_node_id))
     }
 
-    pub async fn query_selector_all(
+    ///Plain CSS selectors are matched in one call, each node is described only when the stream is polled.
+    ///Queries with `>`, `,` or `text(` steps fall back to an eager `query_selector_all` and only stream its result.
+    ///Nothing matched gives an empty stream, a node that fails to be described yields an error.
+    pub async fn query_selector_all_stream(
         self: &Arc<Self>,
         query: &str,
         backend_node_id: Option<BackendNodeId>,
-    ) -> Result<Vec<Element>> {
-        let _ = self.dom_lock().await.lock().await;
+    ) -> Result<BoxStream<'static, Result<Element>>> {
+        if !is_plain_css(query) {
+            let elements = self
+                .find_all(query, backend_node_id)
+                .await?
+                .unwrap_or_default();
+            return Ok(stream::iter(elements.into_iter().map(Ok)).boxed());
+        }
+
+        let root = match backend_node_id {
+            Some(backend_node_id) => backend_node_id,
+            None => self.root_backend_node_id().await?,
+        };
+        let guard = ObjectGroupGuard {
+            frame_inner: Arc::downgrade(self),
+            object_group: format!(
+                "chromatica-query-{}",
+                NEXT_OBJECT_GROUP.fetch_add(1, Ordering::Relaxed)
+            ),
+        };
+        let root_object_id = self.resolve_node(&root).await?;
+        let selector = CallArgument::from_value(Value::from(query));
+        let params = CallFunctionOn::new(QUERY_ALL_JS)
+            .object_id(&root_object_id)
+            .arguments(vec![&selector])
+            .return_by_value(false)
+            .object_group(&guard.object_group)
+            .build();
+        let response = self.send("Runtime.callFunctionOn", &params).await;
         let _ = self
             .send(
-                "DOM.getDocument",
-                &GetDocument::new().depth(0).pierce(true).build(),
+                "Runtime.releaseObject",
+                &ReleaseObject::default(&root_object_id),
             )
-            .await?;
+            .await;
+        let response = response?.result_as::<CallFunctionOnResponse>()?;
+        if let Some(exception_details) = response.exception_details {
+            return Err(anyhow!(
+                "Query '{}' failed: {}",
+                query,
+                exception_details.message()
+            ));
+        }
+        let Some(array_id) = response.result.and_then(|result| result.object_id) else {
+            return Err(anyhow!("Query '{}' returned no matches array", query));
+        };
+
+        let properties = self
+            .send("Runtime.getProperties", &GetProperties::default(&array_id))
+            .await?
+            .result_as::<GetPropertiesResponse>()?;
+        let mut object_ids: Vec<(usize, RemoteObjectId)> = properties
+            .result
+            .into_iter()
+            .filter_map(|property| {
+                let index = property.name.parse::<usize>().ok()?;
+                Some((index, property.value?.object_id?))
+            })
+            .collect();
+        object_ids.sort_by_key(|(index, _)| *index);
+
+        let frame_inner = Arc::downgrade(self);
+        let stream = stream::iter(object_ids).then(move |(_, object_id)| {
+            let _guard = &guard;
+            let frame_inner = frame_inner.clone();
+            async move {
+                let Some(frame_inner) = frame_inner.upgrade() else {
+                    return Err(anyhow!("Frame inner is dropped"));
+                };
+                frame_inner.describe_object(&object_id).await
+            }
+        });
+        Ok(stream.boxed())
+    }
+
+    //Frame owners resolve to the root of their frame, as in query_selector_all.
+    async fn describe_object(self: &Arc<Self>, object_id: &RemoteObjectId) -> Result<Element> {
+        let node = self
+            .send(
+                "DOM.describeNode",
+                &DescribeNode::new().object_id(object_id).build(),
+            )
+            .await?
+            .result_as::<DescribeNodeResponse>()?
+            .node;
+        if let Some(frame_id) = node.frame_id {
+            let Some(frame_inner) = self.target_manager().await.get_frame_inner(&frame_id).await
+            else {
+                return Err(anyhow!("Frame {} is not attached", frame_id));
+            };
+            let backend_node_id = frame_inner.root_backend_node_id().await?;
+            return Ok(Element::new(Arc::downgrade(&frame_inner), backend_node_id));
+        }
+        Ok(Element::new(Arc::downgrade(self), node.backend_node_id))
+    }
+
+    pub async fn query_selector_all(
+        self: &Arc<Self>,
+        query: &str,
+        backend_node_id: Option<BackendNodeId>,
+    ) -> Result<Vec<Element>> {
+        match self.find_all(query, backend_node_id).await? {
+            Some(elements) => Ok(elements),
+            None => Err(anyhow!("No elements found")),
+        }
+    }
+
+    ///Elements matching `query`, `None` when nothing matches.
+    async fn find_all(
+        self: &Arc<Self>,
+        query: &str,
+        backend_node_id: Option<BackendNodeId>,
+    ) -> Result<Option<Vec<Element>>> {
+        let _ = self.dom_lock().await.lock().await;
+        self.send(
+            "DOM.getDocument",
+            &GetDocument::new().depth(0).pierce(true).build(),
+        )
+        .await?
+        .result_as::<Value>()?;
         let query_builder = QueryBuilder::new(query, Arc::downgrade(self), backend_node_id);
-        let result = query_builder.parse_all().await?;
-        let vec = match result {
-            Some(vec) => vec,
-            None => return Err(anyhow!("No elements found")),
+        let Some(nodes) = query_builder.parse_all().await? else {
+            return Ok(None);
         };
-        let elements = vec
+        let elements = nodes
             .into_iter()
             .map(|(backend_node_id, frame_inner)| {
                 Element::new(Arc::downgrade(&frame_inner), backend_node_id)
             })
             .collect();
-        Ok(elements)
+        Ok(Some(elements))
     }
 
     // pub async fn wait_for_selector(
@@ -726,7 +1474,10 @@ impl FrameInner {
                         // }
                     }
                     Err(broadcast::error::RecvError::Closed) => {
-                        return Err(anyhow!("Channel closed"));
+                        return Err(anyhow!(
+                            "Waiting for selector '{}' failed: DOM event channel closed, target is probably destroyed",
+                            query_str
+                        ));
                     }
                 }
             }
@@ -751,17 +1502,190 @@ impl FrameInner {
                 Err(_) => {
                     // println!("Wait for selector timed out: {:?}", e);
                     handle.abort();
-                    Err(anyhow!("Wait for selector timed out"))
+                    Err(anyhow!(
+                        "Waiting for selector '{}' timed out after {:?}",
+                        query,
+                        timeout
+                    ))
                 }
             }
         }
     }
 
-    async fn wait_for_file_chooser(
+    ///Waits until any of `queries` matches and returns its index with the element.
+    ///Queries are checked in order, so the first one wins when several match at once.
+    pub async fn wait_for_any_selector(
         self: &Arc<Self>,
+        queries: &[&str],
         timeout: Option<Duration>,
-    ) -> Result<FileChooser> {
-        let timeout = timeout.or(Some(self.default_timeout().await)).unwrap();
+    ) -> Result<(usize, Element)> {
+        if queries.is_empty() {
+            return Err(anyhow!("No selectors to wait for"));
+        }
+        let timeout = timeout.unwrap_or(self.default_timeout().await);
+        let queries: Vec<String> = queries.iter().map(|query| query.to_string()).collect();
+
+        let self_clone = self.clone();
+        let queries_clone = queries.clone();
+        let mut handle: JoinHandle<Result<(usize, Element)>> = tokio::spawn(async move {
+            let mut dom_events = self_clone.js_manager().await.subscribe_to_dom_events();
+            loop {
+                if let Some(matched) = self_clone.query_any_selector(&queries_clone).await {
+                    return Ok(matched);
+                }
+                match dom_events.recv().await {
+                    Ok(()) | Err(broadcast::error::RecvError::Lagged(_)) => {
+                        tokio::time::sleep(Duration::from_millis(10)).await;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => {
+                        return Err(anyhow!(
+                            "Waiting for selectors {:?} failed: DOM event channel closed, target is probably destroyed",
+                            queries_clone
+                        ));
+                    }
+                }
+            }
+        });
+
+        if timeout.is_zero() {
+            handle.await?
+        } else {
+            match tokio::time::timeout(timeout, &mut handle).await {
+                Ok(result) => result?,
+                Err(_) => {
+                    handle.abort();
+                    Err(anyhow!(
+                        "Waiting for selectors {:?} timed out after {:?}",
+                        queries,
+                        timeout
+                    ))
+                }
+            }
+        }
+    }
+
+    async fn query_any_selector(self: &Arc<Self>, queries: &[String]) -> Option<(usize, Element)> {
+        for (index, query) in queries.iter().enumerate() {
+            if let Ok(element) = self.query_selector(query, None).await {
+                return Some((index, element));
+            }
+        }
+        None
+    }
+
+    ///Polls `function` with `args` until it returns a truthy value. A node result is returned as an element.
+    pub async fn wait_for_function(
+        self: &Arc<Self>,
+        function: &str,
+        args: Vec<Value>,
+        timeout: Option<Duration>,
+    ) -> Result<Option<Element>> {
+        let timeout = timeout.unwrap_or(self.default_timeout().await);
+        let function_declaration = format!(
+            "async function(...args) {{ const result = await ({})(...args); return result ? result : null; }}",
+            function
+        );
+        let arguments: Vec<CallArgument> = args.into_iter().map(CallArgument::from_value).collect();
+
+        let self_clone = self.clone();
+        let mut handle: JoinHandle<Result<Option<Element>>> = tokio::spawn(async move {
+            loop {
+                if let Some(result) = self_clone
+                    .poll_function(&function_declaration, &arguments)
+                    .await?
+                {
+                    return Ok(result);
+                }
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+        });
+
+        if timeout.is_zero() {
+            handle.await?
+        } else {
+            match tokio::time::timeout(timeout, &mut handle).await {
+                Ok(result) => result?,
+                Err(_) => {
+                    handle.abort();
+                    Err(anyhow!(
+                        "Waiting for function timed out after {:?}",
+                        timeout
+                    ))
+                }
+            }
+        }
+    }
+
+    //None means the predicate is still falsy, Some(None) is a truthy non-node result
+    async fn poll_function(
+        self: &Arc<Self>,
+        function_declaration: &str,
+        arguments: &[CallArgument],
+    ) -> Result<Option<Option<Element>>> {
+        //The global object is looked up on every poll, navigations replace the execution context
+        let global = self
+            .send("Runtime.evaluate", &Evaluate::new("globalThis").build())
+            .await?
+            .result_as::<EvaluateResponse>();
+        let Some(global_id) = global
+            .ok()
+            .and_then(|response| response.result)
+            .and_then(|result| result.object_id)
+        else {
+            return Ok(None);
+        };
+
+        let params = CallFunctionOn::new(function_declaration)
+            .object_id(&global_id)
+            .arguments(arguments.iter().collect())
+            .return_by_value(false)
+            .build();
+        let response = self.send("Runtime.callFunctionOn", &params).await;
+        let _ = self
+            .send("Runtime.releaseObject", &ReleaseObject::default(&global_id))
+            .await;
+        let response = response?.result_as::<CallFunctionOnResponse>()?;
+        if let Some(exception_details) = response.exception_details {
+            return Err(anyhow!(
+                "Evaluation failed: {}",
+                exception_details.message()
+            ));
+        }
+        let Some(result) = response.result else {
+            return Ok(None);
+        };
+        if result.subtype.as_deref() == Some("null") {
+            return Ok(None);
+        }
+        let Some(object_id) = result.object_id else {
+            return Ok(Some(None));
+        };
+
+        let element = if result.subtype.as_deref() == Some("node") {
+            let described = self
+                .send(
+                    "DOM.describeNode",
+                    &DescribeNode::new().object_id(&object_id).build(),
+                )
+                .await
+                .and_then(|response| response.result_as::<DescribeNodeResponse>());
+            Some(described.map(|described| {
+                Element::new(Arc::downgrade(self), described.node.backend_node_id)
+            }))
+        } else {
+            None
+        };
+        let _ = self
+            .send("Runtime.releaseObject", &ReleaseObject::default(&object_id))
+            .await;
+        Ok(Some(element.transpose()?))
+    }
+
+    async fn wait_for_file_chooser(
+        self: &Arc<Self>,
+        timeout: Option<Duration>,
+    ) -> Result<FileChooser> {
+        let timeout = timeout.or(Some(self.default_timeout().await)).unwrap();
         let js_manager = self.js_manager().await;
         let _ = js_manager.set_intercept_file_chooser(true).await;
         let mut file_chooser = js_manager.subscribe_to_file_chooser();
@@ -793,10 +1717,265 @@ impl FrameInner {
         }
     }
 
-    pub async fn click(self: &Arc<Self>, backend_node_id: &BackendNodeId) -> Result<()> {
+    pub async fn resolve_node(&self, backend_node_id: &BackendNodeId) -> Result<RemoteObjectId> {
+        match self.resolve_node_once(backend_node_id).await {
+            Err(e) => match self.refresh_stale_root(backend_node_id).await {
+                Some(root) => self.resolve_node_once(&root).await,
+                None => Err(e),
+            },
+            object_id => object_id,
+        }
+    }
+
+    async fn resolve_node_once(&self, backend_node_id: &BackendNodeId) -> Result<RemoteObjectId> {
+        let response = self
+            .send("DOM.resolveNode", &ResolveNode::default(backend_node_id))
+            .await?;
+        let object = response.result_as::<ResolveNodeResponse>()?.object;
+        match object.object_id {
+            Some(object_id) => Ok(object_id),
+            None => Err(anyhow!("Node has no remote object")),
+        }
+    }
+
+    ///Calls `function_declaration` with the element as `this` and returns the result by value.
+    pub async fn call_function_on(
+        &self,
+        backend_node_id: &BackendNodeId,
+        function_declaration: &str,
+        arguments: Vec<CallArgument>,
+    ) -> Result<Value> {
+        let object_id = self.resolve_node(backend_node_id).await?;
+        let params = CallFunctionOn::new(function_declaration)
+            .object_id(&object_id)
+            .arguments(arguments.iter().collect())
+            .return_by_value(true)
+            .build();
+        let response = self.send("Runtime.callFunctionOn", &params).await;
+        let _ = self
+            .send("Runtime.releaseObject", &ReleaseObject::default(&object_id))
+            .await;
+        let response = response?.result_as::<CallFunctionOnResponse>()?;
+        if let Some(exception_details) = response.exception_details {
+            return Err(anyhow!(
+                "Evaluation failed: {}",
+                exception_details.message()
+            ));
+        }
+        match response.result.and_then(|result| result.value) {
+            Some(value) => Ok(value),
+            None => Ok(Value::Null),
+        }
+    }
+
+    pub async fn matches(&self, backend_node_id: &BackendNodeId, selector: &str) -> Result<bool> {
+        let matches = self
+            .call_function_on(
+                backend_node_id,
+                "function(selector) { return this.matches(selector); }",
+                vec![CallArgument::from_value(Value::from(selector))],
+            )
+            .await?;
+        Ok(matches.as_bool().unwrap_or(false))
+    }
+
+    pub async fn check_visibility(
+        &self,
+        backend_node_id: &BackendNodeId,
+        options: VisibilityOptions,
+    ) -> Result<bool> {
+        let visible = self
+            .call_function_on(
+                backend_node_id,
+                CHECK_VISIBILITY_JS,
+                vec![CallArgument::from_value(serde_json::to_value(options)?)],
+            )
+            .await?;
+        Ok(visible.as_bool().unwrap_or(false))
+    }
+
+    ///Backend node ids can differ between re-queries of the same node, so the resolved objects are compared instead.
+    pub async fn is_same_node(
+        &self,
+        backend_node_id: &BackendNodeId,
+        other_backend_node_id: &BackendNodeId,
+    ) -> Result<bool> {
+        if backend_node_id == other_backend_node_id {
+            return Ok(true);
+        }
+        let other_object_id = self.resolve_node(other_backend_node_id).await?;
+        let same = self
+            .call_function_on(
+                backend_node_id,
+                "function(other) { return this === other; }",
+                vec![CallArgument::from_object_id(other_object_id.clone())],
+            )
+            .await;
+        let _ = self
+            .send(
+                "Runtime.releaseObject",
+                &ReleaseObject::default(&other_object_id),
+            )
+            .await;
+        Ok(same?.as_bool().unwrap_or(false))
+    }
+
+    ///Evaluates `expression` in the target's default context and returns the result by value.
+    pub async fn evaluate_expression(&self, expression: &str) -> Result<Value> {
+        self.evaluate(expression, None).await
+    }
+
+    pub async fn evaluate_as<T: DeserializeOwned>(&self, expression: &str) -> Result<T> {
+        let value = self.evaluate(expression, None).await?;
+        serde_json::from_value(value).map_err(|e| {
+            anyhow!(
+                "Evaluation result doesn't match {}: {}",
+                std::any::type_name::<T>(),
+                e
+            )
+        })
+    }
+
+    ///`timeout` terminates synchronous script on the V8 side, a promise that never settles is cut off here.
+    pub async fn evaluate(&self, expression: &str, timeout: Option<Duration>) -> Result<Value> {
+        let mut params = Evaluate::new(expression).return_by_value(true);
+        if let Some(timeout) = timeout {
+            params = params.timeout(timeout.as_millis() as f64);
+        }
+        let params = params.build();
+        let response = match timeout {
+            Some(timeout) => {
+                match tokio::time::timeout(timeout, self.send("Runtime.evaluate", &params)).await {
+                    Ok(response) => response,
+                    Err(_) => return Err(anyhow!("Evaluation timed out after {:?}", timeout)),
+                }
+            }
+            None => self.send("Runtime.evaluate", &params).await,
+        };
+        let response = response?.result_as::<EvaluateResponse>()?;
+        if let Some(exception_details) = response.exception_details {
+            return Err(anyhow!(
+                "Evaluation failed: {}",
+                exception_details.message()
+            ));
+        }
+        match response.result.and_then(|result| result.value) {
+            Some(value) => Ok(value),
+            None => Ok(Value::Null),
+        }
+    }
+
+    ///Evaluates in the named isolated world of this frame, the same one init scripts registered for it run in.
+    pub async fn evaluate_in_world(&self, world_name: &str, expression: &str) -> Result<Value> {
+        let frame_id = self.frame_id();
+        let context_id = self
+            .send(
+                "Page.createIsolatedWorld",
+                &CreateIsolatedWorld::new(&frame_id)
+                    .world_name(world_name)
+                    .build(),
+            )
+            .await?
+            .result_as::<CreateIsolatedWorldResponse>()?
+            .execution_context_id;
+        let params = Evaluate::new(expression)
+            .context_id(&context_id)
+            .return_by_value(true)
+            .build();
+        let response = self
+            .send("Runtime.evaluate", &params)
+            .await?
+            .result_as::<EvaluateResponse>()?;
+        if let Some(exception_details) = response.exception_details {
+            return Err(anyhow!(
+                "Evaluation failed: {}",
+                exception_details.message()
+            ));
+        }
+        match response.result.and_then(|result| result.value) {
+            Some(value) => Ok(value),
+            None => Ok(Value::Null),
+        }
+    }
+
+    pub async fn scroll_position(&self) -> Result<(f64, f64)> {
+        let position = self.evaluate_expression("[scrollX, scrollY]").await?;
+        match (position[0].as_f64(), position[1].as_f64()) {
+            (Some(x), Some(y)) => Ok((x, y)),
+            _ => Err(anyhow!("Unexpected scroll position: {}", position)),
+        }
+    }
+
+    ///Instant scroll, so `scroll-behavior: smooth` doesn't leave the position in flight.
+    pub async fn scroll_to(&self, x: f64, y: f64) -> Result<()> {
+        //NaN or inf would be formatted into invalid JS
+        if !x.is_finite() || !y.is_finite() {
+            return Err(anyhow!(
+                "Scroll position must be finite, got ({}, {})",
+                x,
+                y
+            ));
+        }
+        let expression = format!(
+            "scrollTo({{ left: {}, top: {}, behavior: 'instant' }})",
+            x, y
+        );
+        self.evaluate_expression(&expression).await?;
+        Ok(())
+    }
+
+    pub async fn matches_media(&self, query: &str) -> Result<bool> {
+        let expression = format!("matchMedia({}).matches", serde_json::to_string(query)?);
+        let matches = self.evaluate_expression(&expression).await?;
+        matches
+            .as_bool()
+            .ok_or_else(|| anyhow!("Unexpected matchMedia result: {}", matches))
+    }
+
+    ///Waits until the bounding box stays the same across two animation frames.
+    pub async fn wait_for_stable(
+        self: &Arc<Self>,
+        backend_node_id: &BackendNodeId,
+        timeout: Option<Duration>,
+    ) -> Result<()> {
+        let timeout = timeout.unwrap_or(self.default_timeout().await);
         let self_clone = self.clone();
+        let backend_node_id = *backend_node_id;
+
+        let mut handle: JoinHandle<Result<()>> = tokio::spawn(async move {
+            loop {
+                let stable = self_clone
+                    .call_function_on(&backend_node_id, WAIT_FOR_STABLE_JS, vec![])
+                    .await?;
+                if stable.as_bool().unwrap_or(false) {
+                    return Ok(());
+                }
+                tokio::task::yield_now().await;
+            }
+        });
+
+        if timeout.is_zero() {
+            handle.await?
+        } else {
+            match tokio::time::timeout(timeout, &mut handle).await {
+                Ok(result) => result?,
+                Err(_) => {
+                    handle.abort();
+                    Err(anyhow!("Waiting for element to be stable timed out"))
+                }
+            }
+        }
+    }
+
+    //Scrolls the element into view and returns the center of its content box,
+    //or `position` relative to the box's top left corner.
+    async fn pointer_target(
+        self: &Arc<Self>,
+        backend_node_id: &BackendNodeId,
+        position: Option<(f32, f32)>,
+    ) -> Result<(f32, f32)> {
         //Actually we can ignore this error, not all elements are able to scroll into view especially if they are in iframes.
-        match self_clone
+        match self
             .send(
                 "DOM.scrollIntoViewIfNeeded",
                 &ScrollIntoViewIfNeeded::default(backend_node_id),
@@ -807,7 +1986,13 @@ impl FrameInner {
             Err(_) => (),
         };
 
-        let response = match self_clone
+        //A click dispatched in the middle of an animation lands on empty space. Spinners and marquees
+        //never settle, so after a short wait the current position is used, errors surface with the box model.
+        let _ = self
+            .wait_for_stable(backend_node_id, Some(ACTIONABILITY_STABLE_TIMEOUT))
+            .await;
+
+        let response = match self
             .send("DOM.getBoxModel", &GetBoxModel::default(backend_node_id))
             .await
         {
@@ -818,8 +2003,156 @@ impl FrameInner {
 
         let content = response.result_as::<GetBoxModelResponse>()?.model.content;
 
-        let x = content[0] + (content[2] - content[0]) / 2.0;
-        let y = content[1] + (content[5] - content[1]) / 2.0;
+        let (dx, dy) = position.unwrap_or((
+            (content[2] - content[0]) / 2.0,
+            (content[5] - content[1]) / 2.0,
+        ));
+        Ok((content[0] + dx, content[1] + dy))
+    }
+
+    pub async fn hover(self: &Arc<Self>, backend_node_id: &BackendNodeId) -> Result<()> {
+        let (x, y) = self.pointer_target(backend_node_id, None).await?;
+        self.send(
+            "Input.dispatchMouseEvent",
+            &DispatchMouseEvent::default(MouseEventType::MouseMoved, x, y),
+        )
+        .await?
+        .result_as::<Value>()?;
+        Ok(())
+    }
+
+    ///Hovers the element and waits for `child_selector` inside it, e.g. a submenu that opens on hover.
+    pub async fn hover_and_wait(
+        self: &Arc<Self>,
+        backend_node_id: &BackendNodeId,
+        child_selector: &str,
+        timeout: Option<Duration>,
+    ) -> Result<Element> {
+        self.hover(backend_node_id).await?;
+        self.wait_for_selector(child_selector, timeout, Some(*backend_node_id))
+            .await
+    }
+
+    ///Drags with real mouse events, `position` is the drop point relative to the target's top left corner.
+    ///With `html5_fallback` a drag that didn't reach a `drop` listener is replayed as synthetic
+    ///`dragstart`/`dragover`/`drop` events sharing one `DataTransfer`, for HTML5 drag and drop libraries.
+    pub async fn drag_to(
+        self: &Arc<Self>,
+        backend_node_id: &BackendNodeId,
+        target_backend_node_id: &BackendNodeId,
+        position: Option<(f32, f32)>,
+        html5_fallback: bool,
+    ) -> Result<()> {
+        let (from_x, from_y) = self.pointer_target(backend_node_id, None).await?;
+        if html5_fallback {
+            self.call_function_on(target_backend_node_id, WATCH_DROP_JS, vec![])
+                .await?;
+        }
+
+        self.send(
+            "Input.dispatchMouseEvent",
+            &DispatchMouseEvent::default(MouseEventType::MouseMoved, from_x, from_y),
+        )
+        .await?
+        .result_as::<Value>()?;
+        let press_mouse = DispatchMouseEvent::new(MouseEventType::MousePressed, from_x, from_y)
+            .button(MouseButton::Left)
+            .buttons(1)
+            .click_count(1)
+            .build();
+        self.send("Input.dispatchMouseEvent", &press_mouse)
+            .await?
+            .result_as::<Value>()?;
+
+        //Target is resolved after the press, scrolling it into view earlier could move the source.
+        let (to_x, to_y) = self
+            .pointer_target(target_backend_node_id, position)
+            .await?;
+        //Drag libraries start dragging only after the pointer moved a few pixels.
+        for step in 1..=DRAG_STEPS {
+            let progress = step as f32 / DRAG_STEPS as f32;
+            let move_mouse = DispatchMouseEvent::new(
+                MouseEventType::MouseMoved,
+                from_x + (to_x - from_x) * progress,
+                from_y + (to_y - from_y) * progress,
+            )
+            .button(MouseButton::Left)
+            .buttons(1)
+            .build();
+            self.send("Input.dispatchMouseEvent", &move_mouse)
+                .await?
+                .result_as::<Value>()?;
+        }
+        let release_mouse = DispatchMouseEvent::new(MouseEventType::MouseReleased, to_x, to_y)
+            .button(MouseButton::Left)
+            .click_count(1)
+            .build();
+        self.send("Input.dispatchMouseEvent", &release_mouse)
+            .await?
+            .result_as::<Value>()?;
+
+        if !html5_fallback {
+            return Ok(());
+        }
+        //Native drop is delivered from the browser's drag loop, a bit after the release.
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let dropped = self
+            .call_function_on(
+                target_backend_node_id,
+                "function() { return this.__chromatica_dropped === true; }",
+                vec![],
+            )
+            .await?;
+        if dropped.as_bool().unwrap_or(false) {
+            return Ok(());
+        }
+
+        let target_object_id = self.resolve_node(target_backend_node_id).await?;
+        let result = self
+            .call_function_on(
+                backend_node_id,
+                HTML5_DRAG_JS,
+                vec![
+                    CallArgument::from_object_id(target_object_id.clone()),
+                    CallArgument::from_value(Value::from(to_x)),
+                    CallArgument::from_value(Value::from(to_y)),
+                ],
+            )
+            .await;
+        let _ = self
+            .send(
+                "Runtime.releaseObject",
+                &ReleaseObject::default(&target_object_id),
+            )
+            .await;
+        result?;
+        Ok(())
+    }
+
+    ///Records mouse, keyboard, touch and scroll gesture commands sent through this target until stopped.
+    pub async fn start_input_recording(&self) {
+        self.target().await.input_recorder().start();
+    }
+
+    pub async fn stop_input_recording(&self) -> InputRecording {
+        self.target().await.input_recorder().stop()
+    }
+
+    ///Dispatches the recorded commands again, keeping the original gaps between them.
+    pub async fn replay_input(&self, recording: &InputRecording) -> Result<()> {
+        let started = tokio::time::Instant::now();
+        for event in &recording.events {
+            tokio::time::sleep_until(started + Duration::from_millis(event.time)).await;
+            self.send(&event.method, &event.params)
+                .await?
+                .result_as::<Value>()?;
+        }
+        Ok(())
+    }
+
+    pub async fn click(self: &Arc<Self>, backend_node_id: &BackendNodeId) -> Result<()> {
+        let self_clone = self.clone();
+        let (x, y) = self_clone.pointer_target(backend_node_id, None).await?;
 
         match self_clone
             .send(
@@ -871,6 +2204,33 @@ impl FrameInner {
         Ok(())
     }
 
+    ///Unlike a single mouseWheel this produces a real fling, so scroll snapping and momentum based loaders kick in.
+    ///Positive distances scroll up/left, as in CDP.
+    pub async fn scroll_gesture(
+        &self,
+        x: f64,
+        y: f64,
+        x_distance: f64,
+        y_distance: f64,
+        speed: Option<i32>,
+    ) -> Result<()> {
+        let mut params = SynthesizeScrollGesture::new(x, y)
+            .x_distance(x_distance)
+            .y_distance(y_distance)
+            .gesture_source_type(GestureSourceType::Touch);
+        if let Some(speed) = speed {
+            params = params.speed(speed);
+        }
+        match self
+            .send("Input.synthesizeScrollGesture", &params.build())
+            .await?
+            .result_as::<Value>()
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(anyhow!("Scroll gesture failed: {}", e)),
+        }
+    }
+
     pub async fn get_attributes(
         &self,
         backend_node_id: &BackendNodeId,
@@ -893,15 +2253,72 @@ impl FrameInner {
             .await?;
         drop(lock);
         let attributes = response.result_as::<GetAttributesResponse>()?.attributes;
-        let mut result = HashMap::new();
-        for chunk in attributes.chunks(2) {
-            if chunk.len() == 2 {
-                result.insert(chunk[0].clone(), chunk[1].clone());
-            }
+        Ok(attributes_to_map(attributes))
+    }
+
+    ///Resolves all nodes with a single push and pipelines `DOM.getAttributes`, results are in input order.
+    pub async fn get_attributes_for(
+        &self,
+        backend_node_ids: &[BackendNodeId],
+    ) -> Result<Vec<HashMap<String, String>>> {
+        if backend_node_ids.is_empty() {
+            return Ok(vec![]);
+        }
+        let dom_lock = self.dom_lock().await;
+        let lock = dom_lock.lock().await;
+        let node_ids = self
+            .send(
+                "DOM.pushNodesByBackendIdsToFrontend",
+                &PushNodesByBackendIdsToFrontend::default(backend_node_ids.to_vec()),
+            )
+            .await?
+            .result_as::<PushNodesByBackendIdsToFrontendResponse>()?
+            .node_ids;
+
+        let mut responses = Vec::with_capacity(node_ids.len());
+        for chunk in node_ids.chunks(ATTRIBUTES_BATCH_CONCURRENCY) {
+            let params: Vec<GetAttributes> = chunk.iter().map(GetAttributes::default).collect();
+            let futures = params
+                .iter()
+                .map(|params| self.send("DOM.getAttributes", params));
+            responses.extend(join_all(futures).await);
+        }
+        drop(lock);
+
+        let mut result = Vec::with_capacity(responses.len());
+        for response in responses {
+            let attributes = response?.result_as::<GetAttributesResponse>()?.attributes;
+            result.push(attributes_to_map(attributes));
         }
         Ok(result)
     }
 
+    ///Short CSS-like label of the node, e.g. `<button#submit.primary>`.
+    pub async fn describe_element(&self, backend_node_id: &BackendNodeId) -> Result<String> {
+        let response = self
+            .send("DOM.describeNode", &DescribeNode::default(backend_node_id))
+            .await?;
+        let node = response.result_as::<DescribeNodeResponseFull>()?.node;
+        if node.local_name.is_empty() {
+            return Ok(format!("<{}>", node.node_name.to_lowercase()));
+        }
+
+        let attributes = attributes_to_map(node.attributes.unwrap_or_default());
+        let mut description = format!("<{}", node.local_name);
+        if let Some(id) = attributes.get("id").filter(|id| !id.is_empty()) {
+            description.push('#');
+            description.push_str(id);
+        }
+        if let Some(class) = attributes.get("class") {
+            for class in class.split_whitespace() {
+                description.push('.');
+                description.push_str(class);
+            }
+        }
+        description.push('>');
+        Ok(description)
+    }
+
     pub async fn get_text(self: &Arc<Self>, backend_node_id: &BackendNodeId) -> Result<String> {
         let self_clone = self.clone();
         let response = self_clone
@@ -919,6 +2336,97 @@ impl FrameInner {
         }
     }
 
+    ///Rendered text, falls back to textContent for nodes without layout, e.g. in SVG.
+    pub async fn get_inner_text(&self, backend_node_id: &BackendNodeId) -> Result<String> {
+        let text = self
+            .call_function_on(
+                backend_node_id,
+                "function() { return this.innerText ?? this.textContent ?? ''; }",
+                vec![],
+            )
+            .await?;
+        match text {
+            Value::String(text) => Ok(text),
+            text => Err(anyhow!("Unexpected inner text: {}", text)),
+        }
+    }
+
+    ///Re-checks `innerText` on DOM mutations, with a slow fallback poll for subtrees the DOM agent doesn't report.
+    pub async fn wait_for_text<F>(
+        self: &Arc<Self>,
+        backend_node_id: &BackendNodeId,
+        predicate: F,
+        timeout: Option<Duration>,
+    ) -> Result<String>
+    where
+        F: Fn(&str) -> bool + Send + 'static,
+    {
+        let timeout = timeout.unwrap_or(self.default_timeout().await);
+        let self_clone = self.clone();
+        let backend_node_id = *backend_node_id;
+
+        let mut handle: JoinHandle<Result<String>> = tokio::spawn(async move {
+            let mut dom_events = self_clone.js_manager().await.subscribe_to_dom_events();
+            loop {
+                let text = self_clone.get_inner_text(&backend_node_id).await?;
+                if predicate(&text) {
+                    return Ok(text);
+                }
+                match tokio::time::timeout(Duration::from_millis(250), dom_events.recv()).await {
+                    Ok(Ok(())) | Ok(Err(broadcast::error::RecvError::Lagged(_))) | Err(_) => {}
+                    Ok(Err(broadcast::error::RecvError::Closed)) => {
+                        return Err(anyhow!(
+                            "Waiting for text failed: DOM event channel closed, target is probably destroyed"
+                        ));
+                    }
+                }
+            }
+        });
+
+        if timeout.is_zero() {
+            handle.await?
+        } else {
+            match tokio::time::timeout(timeout, &mut handle).await {
+                Ok(result) => result?,
+                Err(_) => {
+                    handle.abort();
+                    Err(anyhow!("Waiting for text timed out after {:?}", timeout))
+                }
+            }
+        }
+    }
+
+    pub async fn get_value(&self, backend_node_id: &BackendNodeId) -> Result<String> {
+        let value = self
+            .call_function_on(
+                backend_node_id,
+                "function() { if (!('value' in this)) throw new Error('Element has no value property'); return String(this.value); }",
+                vec![],
+            )
+            .await?;
+        match value {
+            Value::String(value) => Ok(value),
+            value => Err(anyhow!("Unexpected value: {}", value)),
+        }
+    }
+
+    ///Goes through the prototype's setter, so frameworks tracking `value` (e.g. React) see the change.
+    pub async fn set_value(&self, backend_node_id: &BackendNodeId, value: &str) -> Result<()> {
+        self.call_function_on(
+            backend_node_id,
+            SET_VALUE_JS,
+            vec![CallArgument::from_value(Value::from(value))],
+        )
+        .await?;
+        Ok(())
+    }
+
+    pub async fn clear(&self, backend_node_id: &BackendNodeId) -> Result<()> {
+        self.call_function_on(backend_node_id, CLEAR_JS, vec![])
+            .await?;
+        Ok(())
+    }
+
     pub async fn type_text(
         self: &Arc<Self>,
         backend_node_id: &BackendNodeId,
@@ -998,6 +2506,20 @@ impl FrameInner {
         Ok(())
     }
 
+    ///Installs the observers on first use, so vitals of later navigations are recorded from the start.
+    pub async fn web_vitals(self: &Arc<Self>) -> Result<WebVitals> {
+        if !self.web_vitals_installed.swap(true, Ordering::SeqCst)
+            && let Err(e) = self.add_evaluate_on_new_document(WEB_VITALS_JS).await
+        {
+            self.web_vitals_installed.store(false, Ordering::SeqCst);
+            return Err(e);
+        }
+        let vitals = self
+            .evaluate_expression(&format!("{};\n{}", WEB_VITALS_JS, READ_WEB_VITALS_JS))
+            .await?;
+        Ok(serde_json::from_value(vitals)?)
+    }
+
     pub async fn add_evaluate_on_new_document(
         self: &Arc<Self>,
         script: &str,
@@ -1016,6 +2538,23 @@ impl FrameInner {
         Ok(script_identifier)
     }
 
+    ///Page scripts can't see or tamper with globals of an isolated world.
+    pub async fn add_evaluate_on_new_document_in_world(
+        self: &Arc<Self>,
+        script: &str,
+        world_name: &str,
+    ) -> Result<ScriptIdentifier> {
+        let params = AddScriptToEvaluateOnNewDocument::default(script)
+            .world_name(world_name)
+            .build();
+        let response = self
+            .send("Page.addScriptToEvaluateOnNewDocument", &params)
+            .await?;
+        Ok(response
+            .result_as::<AddScriptToEvaluateOnNewDocumentResponse>()?
+            .identifier)
+    }
+
     pub async fn remove_evaluate_on_new_document(
         self: &Arc<Self>,
         script_identifier: &ScriptIdentifier,
@@ -1045,4 +2584,776 @@ impl FrameInner {
             .await?;
         Ok(())
     }
+
+    pub async fn add_virtual_authenticator(
+        &self,
+        options: &VirtualAuthenticatorOptions,
+    ) -> Result<AuthenticatorId> {
+        self.send("WebAuthn.enable", &WebAuthnEnable::default())
+            .await?
+            .result_as::<Value>()?;
+        let response = self
+            .send(
+                "WebAuthn.addVirtualAuthenticator",
+                &AddVirtualAuthenticator::default(options),
+            )
+            .await?;
+        let authenticator_id = response
+            .result_as::<AddVirtualAuthenticatorResponse>()?
+            .authenticator_id;
+        Ok(authenticator_id)
+    }
+
+    pub async fn remove_virtual_authenticator(
+        &self,
+        authenticator_id: &AuthenticatorId,
+    ) -> Result<()> {
+        self.send(
+            "WebAuthn.removeVirtualAuthenticator",
+            &RemoveVirtualAuthenticator::default(authenticator_id),
+        )
+        .await?
+        .result_as::<Value>()?;
+        Ok(())
+    }
+
+    pub async fn add_credential(
+        &self,
+        authenticator_id: &AuthenticatorId,
+        credential: &Credential,
+    ) -> Result<()> {
+        self.send(
+            "WebAuthn.addCredential",
+            &AddCredential::default(authenticator_id, credential),
+        )
+        .await?
+        .result_as::<Value>()?;
+        Ok(())
+    }
+
+    pub async fn get_credentials(
+        &self,
+        authenticator_id: &AuthenticatorId,
+    ) -> Result<Vec<Credential>> {
+        let response = self
+            .send(
+                "WebAuthn.getCredentials",
+                &GetCredentials::default(authenticator_id),
+            )
+            .await?;
+        let credentials = response.result_as::<GetCredentialsResponse>()?.credentials;
+        Ok(credentials)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::mock::{MockBrowser, NO_REPLY, default_reply, eventually, session_for};
+    use super::*;
+    use serde_json::json;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn inserts_base_url_into_head() {
+        assert_eq!(
+            with_base_url(
+                "<html><HEAD lang=\"en\"><title>t</title></HEAD></html>",
+                "https://example.com/mail/"
+            ),
+            "<html><HEAD lang=\"en\"><base href=\"https://example.com/mail/\"><title>t</title></HEAD></html>"
+        );
+        assert_eq!(
+            with_base_url("<img src=\"logo.png\">", "file:///tmp/a&b/"),
+            "<base href=\"file:///tmp/a&amp;b/\"><img src=\"logo.png\">"
+        );
+    }
+
+    #[tokio::test]
+    async fn wait_for_function_polls_until_truthy_and_returns_node() {
+        let polls = Arc::new(AtomicUsize::new(0));
+        let polls_clone = polls.clone();
+        let browser = MockBrowser::start(move |method, params, session_id| match method {
+            "Runtime.evaluate" => Ok(json!({ "result": { "type": "object", "objectId": "GLOBAL" } })),
+            "Runtime.callFunctionOn" => {
+                if polls_clone.fetch_add(1, Ordering::SeqCst) < 2 {
+                    Ok(json!({ "result": { "type": "object", "subtype": "null" } }))
+                } else {
+                    Ok(json!({ "result": { "type": "object", "subtype": "node", "objectId": "NODE" } }))
+                }
+            }
+            "DOM.describeNode" => Ok(json!({ "node": { "backendNodeId": 7, "nodeType": 1, "nodeName": "UL", "localName": "ul", "nodeValue": "" } })),
+            _ => default_reply(method, params, session_id),
+        })
+        .await;
+        let (_connection, _, frame_inner) = browser.page_frame().await;
+
+        let element = frame_inner
+            .wait_for_function(
+                "(sel, count) => document.querySelectorAll(sel).length > count && document.querySelector(sel)",
+                vec![json!("li"), json!(5)],
+                Some(Duration::from_secs(5)),
+            )
+            .await
+            .unwrap();
+        assert!(element.is_some());
+        assert_eq!(polls.load(Ordering::SeqCst), 3);
+
+        let calls = browser.commands("Runtime.callFunctionOn");
+        assert_eq!(
+            calls[0].params["arguments"],
+            json!([{ "value": "li" }, { "value": 5 }])
+        );
+        assert_eq!(calls[0].params["objectId"], "GLOBAL");
+        assert_eq!(
+            browser.commands("DOM.describeNode")[0].params["objectId"],
+            "NODE"
+        );
+    }
+
+    #[tokio::test]
+    async fn document_root_is_cached_until_navigation() {
+        let browser = MockBrowser::start(|method, params, session_id| match method {
+            "DOM.getDocument" => Ok(json!({ "root": { "backendNodeId": 1, "nodeType": 9 } })),
+            _ => default_reply(method, params, session_id),
+        })
+        .await;
+        let (_connection, _, frame_inner) = browser.page_frame().await;
+
+        assert_eq!(frame_inner.root_backend_node_id().await.unwrap(), 1);
+        assert_eq!(frame_inner.root_backend_node_id().await.unwrap(), 1);
+        assert_eq!(browser.commands("DOM.getDocument").len(), 1);
+
+        browser.emit(
+            "Page.frameNavigated",
+            json!({ "frame": { "id": "PAGE", "url": "https://example.com/next" } }),
+            Some(&session_for("PAGE")),
+        );
+        eventually(|| async { frame_inner.document_root.read().await.is_none() }).await;
+
+        frame_inner.root_backend_node_id().await.unwrap();
+        assert_eq!(browser.commands("DOM.getDocument").len(), 2);
+    }
+
+    #[tokio::test]
+    async fn stale_document_root_is_fetched_again() {
+        let documents = Arc::new(AtomicUsize::new(0));
+        let documents_clone = documents.clone();
+        let browser = MockBrowser::start(move |method, params, session_id| match method {
+            "DOM.getDocument" => {
+                let root = documents_clone.fetch_add(1, Ordering::SeqCst) + 1;
+                Ok(json!({ "root": { "backendNodeId": root, "nodeType": 9 } }))
+            }
+            //The first document was replaced by a navigation
+            "DOM.resolveNode" if params["backendNodeId"] == 1 => {
+                Err("No node with given id found".to_string())
+            }
+            "DOM.resolveNode" => Ok(json!({ "object": { "type": "object", "objectId": "ROOT" } })),
+            _ => default_reply(method, params, session_id),
+        })
+        .await;
+        let (_connection, _, frame_inner) = browser.page_frame().await;
+
+        let root = frame_inner.root_backend_node_id().await.unwrap();
+        assert_eq!(root, 1);
+        assert_eq!(frame_inner.resolve_node(&root).await.unwrap(), "ROOT");
+        assert_eq!(frame_inner.root_backend_node_id().await.unwrap(), 2);
+        assert_eq!(documents.load(Ordering::SeqCst), 2);
+
+        //Other nodes which are gone are still an error
+        assert!(frame_inner.resolve_node(&1).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn reads_attributes_of_many_nodes_with_one_push() {
+        let browser = MockBrowser::start(|method, params, session_id| match method {
+            "DOM.pushNodesByBackendIdsToFrontend" => {
+                let node_ids: Vec<i64> = params["backendNodeIds"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|id| id.as_i64().unwrap() + 100)
+                    .collect();
+                Ok(json!({ "nodeIds": node_ids }))
+            }
+            "DOM.getAttributes" => {
+                Ok(json!({ "attributes": ["data-id", params["nodeId"].to_string()] }))
+            }
+            _ => default_reply(method, params, session_id),
+        })
+        .await;
+        let (_connection, _, frame_inner) = browser.page_frame().await;
+
+        let backend_node_ids: Vec<BackendNodeId> = (1..=40).collect();
+        let attributes = frame_inner
+            .get_attributes_for(&backend_node_ids)
+            .await
+            .unwrap();
+        assert_eq!(attributes.len(), 40);
+        assert_eq!(attributes[0]["data-id"], "101");
+        assert_eq!(attributes[39]["data-id"], "140");
+        assert_eq!(
+            browser
+                .commands("DOM.pushNodesByBackendIdsToFrontend")
+                .len(),
+            1
+        );
+        assert_eq!(browser.commands("DOM.getAttributes").len(), 40);
+    }
+
+    #[tokio::test]
+    async fn evaluate_passes_timeout_to_v8() {
+        let browser = MockBrowser::start(|method, params, session_id| match method {
+            "Runtime.evaluate" => Ok(json!({ "result": { "type": "number", "value": 2 } })),
+            _ => default_reply(method, params, session_id),
+        })
+        .await;
+        let (_connection, _, frame_inner) = browser.page_frame().await;
+
+        let value = frame_inner
+            .evaluate("1 + 1", Some(Duration::from_millis(1500)))
+            .await
+            .unwrap();
+        assert_eq!(value, json!(2));
+        frame_inner.evaluate_expression("1 + 1").await.unwrap();
+
+        let evaluations = browser.commands("Runtime.evaluate");
+        assert_eq!(evaluations[0].params["timeout"], json!(1500.0));
+        assert!(evaluations[1].params.get("timeout").is_none());
+    }
+
+    #[tokio::test]
+    async fn evaluate_times_out_when_browser_never_replies() {
+        let browser = MockBrowser::start(|method, params, session_id| match method {
+            "Runtime.evaluate" => Err(NO_REPLY.to_string()),
+            _ => default_reply(method, params, session_id),
+        })
+        .await;
+        let (_connection, _, frame_inner) = browser.page_frame().await;
+
+        let error = frame_inner
+            .evaluate("new Promise(() => {})", Some(Duration::from_millis(100)))
+            .await
+            .unwrap_err();
+        assert!(error.to_string().starts_with("Evaluation timed out"));
+        assert_eq!(browser.commands("Runtime.evaluate").len(), 1);
+    }
+
+    #[tokio::test]
+    async fn wait_for_text_rechecks_on_dom_events() {
+        let checks = Arc::new(AtomicUsize::new(0));
+        let checks_clone = checks.clone();
+        let browser = MockBrowser::start(move |method, params, session_id| match method {
+            "DOM.resolveNode" => Ok(json!({ "object": { "type": "object", "objectId": "NODE" } })),
+            "Runtime.callFunctionOn" => {
+                let text = if checks_clone.fetch_add(1, Ordering::SeqCst) < 1 {
+                    "Processing…"
+                } else {
+                    "Done"
+                };
+                Ok(json!({ "result": { "type": "string", "value": text } }))
+            }
+            _ => default_reply(method, params, session_id),
+        })
+        .await;
+        let (_connection, _, frame_inner) = browser.page_frame().await;
+
+        let waiting = tokio::spawn({
+            let frame_inner = frame_inner.clone();
+            async move {
+                frame_inner
+                    .wait_for_text(&7, |text| text == "Done", Some(Duration::from_secs(5)))
+                    .await
+            }
+        });
+        eventually(|| async { checks.load(Ordering::SeqCst) == 1 }).await;
+        let emitted_at = tokio::time::Instant::now();
+        browser.emit(
+            "DOM.characterDataModified",
+            json!({ "nodeId": 3, "characterData": "Done" }),
+            Some(&session_for("PAGE")),
+        );
+
+        assert_eq!(waiting.await.unwrap().unwrap(), "Done");
+        assert_eq!(checks.load(Ordering::SeqCst), 2);
+        //The fallback poll would only re-check 250ms after the first check.
+        assert!(emitted_at.elapsed() < Duration::from_millis(150));
+    }
+
+    #[tokio::test]
+    async fn print_element_restores_page_when_printing_fails() {
+        let browser = MockBrowser::start(|method, params, session_id| match method {
+            "DOM.resolveNode" => Ok(json!({ "object": { "type": "object", "objectId": "NODE" } })),
+            "Runtime.callFunctionOn" => Ok(json!({ "result": { "type": "undefined" } })),
+            "Page.printToPDF" => Err("Printing failed".to_string()),
+            _ => default_reply(method, params, session_id),
+        })
+        .await;
+        let (_connection, _, frame_inner) = browser.page_frame().await;
+
+        assert!(
+            frame_inner
+                .print_element_to_pdf(&7, None, None)
+                .await
+                .is_err()
+        );
+
+        let isolate = browser.commands("Runtime.callFunctionOn");
+        assert_eq!(
+            isolate[0].params["functionDeclaration"],
+            json!(ISOLATE_FOR_PRINT_JS)
+        );
+        let restore = browser.commands("Runtime.evaluate");
+        assert_eq!(
+            restore.last().unwrap().params["expression"],
+            json!(RESTORE_AFTER_PRINT_JS)
+        );
+    }
+
+    #[tokio::test]
+    async fn web_vitals_installs_observers_once() {
+        let browser = MockBrowser::start(|method, params, session_id| match method {
+            "Page.addScriptToEvaluateOnNewDocument" => Ok(json!({ "identifier": "1" })),
+            "Runtime.evaluate" => Ok(json!({ "result": { "type": "object", "value": {
+                "lcp": 812.5, "cls": 0.02, "fid": null, "inp": null, "fcp": 301.0
+            } } })),
+            _ => default_reply(method, params, session_id),
+        })
+        .await;
+        let (_connection, _, frame_inner) = browser.page_frame().await;
+
+        let vitals = frame_inner.web_vitals().await.unwrap();
+        frame_inner.web_vitals().await.unwrap();
+
+        assert_eq!(vitals.largest_contentful_paint, Some(812.5));
+        assert_eq!(vitals.cumulative_layout_shift, 0.02);
+        assert_eq!(vitals.first_input_delay, None);
+        assert_eq!(vitals.first_contentful_paint, Some(301.0));
+        assert_eq!(
+            browser
+                .commands("Page.addScriptToEvaluateOnNewDocument")
+                .len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn drag_to_replays_html5_events_when_nothing_dropped() {
+        let browser = MockBrowser::start(|method, params, session_id| match method {
+            "DOM.resolveNode" => Ok(json!({ "object": { "type": "object", "objectId": "NODE" } })),
+            "DOM.getBoxModel" => {
+                let quad = if params["backendNodeId"] == json!(1) {
+                    json!([0, 0, 100, 0, 100, 50, 0, 50])
+                } else {
+                    json!([200, 100, 300, 100, 300, 200, 200, 200])
+                };
+                Ok(json!({ "model": {
+                    "content": quad, "padding": quad, "border": quad, "margin": quad,
+                    "width": 100, "height": 50
+                } }))
+            }
+            "Runtime.callFunctionOn" => {
+                let stable = params["functionDeclaration"] == json!(WAIT_FOR_STABLE_JS);
+                Ok(json!({ "result": { "type": "boolean", "value": stable } }))
+            }
+            _ => default_reply(method, params, session_id),
+        })
+        .await;
+        let (_connection, _, frame_inner) = browser.page_frame().await;
+
+        frame_inner
+            .drag_to(&1, &2, Some((10.0, 20.0)), true)
+            .await
+            .unwrap();
+
+        let mouse_events = browser.commands("Input.dispatchMouseEvent");
+        assert_eq!(mouse_events.len(), DRAG_STEPS + 3);
+        assert_eq!(mouse_events[1].params["type"], json!("mousePressed"));
+        assert_eq!(
+            (&mouse_events[1].params["x"], &mouse_events[1].params["y"]),
+            (&json!(50.0), &json!(25.0))
+        );
+        let release = mouse_events.last().unwrap();
+        assert_eq!(release.params["type"], json!("mouseReleased"));
+        assert_eq!(
+            (&release.params["x"], &release.params["y"]),
+            (&json!(210.0), &json!(120.0))
+        );
+
+        let replay = browser.commands("Runtime.callFunctionOn");
+        let replay = replay.last().unwrap();
+        assert_eq!(replay.params["functionDeclaration"], json!(HTML5_DRAG_JS));
+        assert_eq!(replay.params["arguments"][1]["value"], json!(210.0));
+    }
+
+    #[tokio::test]
+    async fn hover_gives_up_waiting_for_elements_that_never_settle() {
+        let browser = MockBrowser::start(|method, params, session_id| match method {
+            "DOM.resolveNode" => Ok(json!({ "object": { "type": "object", "objectId": "NODE" } })),
+            "DOM.getBoxModel" => {
+                let quad = json!([0, 0, 100, 0, 100, 50, 0, 50]);
+                Ok(json!({ "model": {
+                    "content": quad, "padding": quad, "border": quad, "margin": quad,
+                    "width": 100, "height": 50
+                } }))
+            }
+            "Runtime.callFunctionOn" => {
+                Ok(json!({ "result": { "type": "boolean", "value": false } }))
+            }
+            _ => default_reply(method, params, session_id),
+        })
+        .await;
+        let (_connection, _, frame_inner) = browser.page_frame().await;
+
+        frame_inner.hover(&1).await.unwrap();
+        let mouse_events = browser.commands("Input.dispatchMouseEvent");
+        assert_eq!(mouse_events.len(), 1);
+        assert_eq!(
+            (&mouse_events[0].params["x"], &mouse_events[0].params["y"]),
+            (&json!(50.0), &json!(25.0))
+        );
+    }
+
+    #[tokio::test]
+    async fn wait_for_any_selector_returns_index_of_first_match() {
+        let error_shown = Arc::new(AtomicBool::new(false));
+        let error_shown_clone = error_shown.clone();
+        let browser = MockBrowser::start(move |method, params, session_id| match method {
+            "DOM.getDocument" => Ok(json!({ "root": { "backendNodeId": 1, "nodeType": 9 } })),
+            "DOM.pushNodesByBackendIdsToFrontend" => Ok(json!({ "nodeIds": [1] })),
+            "DOM.querySelector"
+                if params["selector"] == ".error" && error_shown_clone.load(Ordering::SeqCst) =>
+            {
+                Ok(json!({ "nodeId": 7 }))
+            }
+            "DOM.querySelector" => Ok(json!({ "nodeId": 0 })),
+            "DOM.describeNode" if params["nodeId"] == 7 => {
+                Ok(json!({ "node": { "backendNodeId": 42, "nodeType": 1 } }))
+            }
+            "DOM.describeNode" => Err("Could not find node with given id".to_string()),
+            _ => default_reply(method, params, session_id),
+        })
+        .await;
+        let (_connection, _, frame_inner) = browser.page_frame().await;
+
+        let waiting = tokio::spawn({
+            let frame_inner = frame_inner.clone();
+            async move {
+                frame_inner
+                    .wait_for_any_selector(&["#dashboard", ".error"], Some(Duration::from_secs(5)))
+                    .await
+            }
+        });
+        eventually(|| async { browser.commands("DOM.querySelector").len() >= 2 }).await;
+        error_shown.store(true, Ordering::SeqCst);
+        browser.emit(
+            "DOM.childNodeInserted",
+            json!({ "parentNodeId": 1, "previousNodeId": 0, "node": { "nodeId": 7, "backendNodeId": 42, "nodeType": 1 } }),
+            Some(&session_for("PAGE")),
+        );
+
+        let (index, element) = waiting.await.unwrap().unwrap();
+        assert_eq!(index, 1);
+        assert_eq!(element.backend_node_id(), 42);
+    }
+
+    #[tokio::test]
+    async fn close_and_wait_returns_after_target_is_destroyed() {
+        let browser = MockBrowser::start(default_reply).await;
+        let (_connection, target_manager, frame_inner) = browser.page_frame().await;
+
+        let closing = tokio::spawn({
+            let frame_inner = frame_inner.clone();
+            async move {
+                frame_inner
+                    .close_and_wait(Some(Duration::from_secs(5)))
+                    .await
+            }
+        });
+        eventually(|| async { !browser.commands("Target.closeTarget").is_empty() }).await;
+        assert!(!closing.is_finished());
+        browser.emit(
+            "Target.targetDestroyed",
+            json!({ "targetId": "PAGE" }),
+            None,
+        );
+
+        closing.await.unwrap().unwrap();
+        assert!(
+            target_manager
+                .get_frame_inner(&"PAGE".to_string())
+                .await
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn query_selector_all_stream_describes_nodes_on_demand() {
+        let browser = MockBrowser::start(|method, params, session_id| match method {
+            "DOM.getDocument" => Ok(json!({ "root": { "backendNodeId": 1, "nodeType": 9 } })),
+            "DOM.resolveNode" => Ok(json!({ "object": { "type": "object", "objectId": "ROOT" } })),
+            "Runtime.callFunctionOn" => Ok(json!({ "result": { "type": "object", "subtype": "array", "objectId": "ROWS" } })),
+            "Runtime.getProperties" => Ok(json!({ "result": [
+                { "name": "1", "value": { "type": "object", "subtype": "node", "objectId": "ROW-1" }, "configurable": true, "enumerable": true },
+                { "name": "0", "value": { "type": "object", "subtype": "node", "objectId": "ROW-0" }, "configurable": true, "enumerable": true },
+                { "name": "length", "value": { "type": "number", "value": 2 }, "configurable": false, "enumerable": false },
+            ] })),
+            "DOM.describeNode" => {
+                let backend_node_id = if params["objectId"] == "ROW-0" { 10 } else { 11 };
+                Ok(json!({ "node": { "backendNodeId": backend_node_id, "nodeType": 1 } }))
+            }
+            _ => default_reply(method, params, session_id),
+        })
+        .await;
+        let (_connection, _, frame_inner) = browser.page_frame().await;
+
+        let mut rows = frame_inner
+            .query_selector_all_stream("tr.row", None)
+            .await
+            .unwrap();
+        assert!(browser.commands("DOM.describeNode").is_empty());
+
+        let first = rows.next().await.unwrap().unwrap();
+        assert_eq!(first.backend_node_id(), 10);
+        assert_eq!(browser.commands("DOM.describeNode").len(), 1);
+
+        let object_group =
+            browser.commands("Runtime.callFunctionOn")[0].params["objectGroup"].clone();
+        drop(rows);
+        eventually(|| async { !browser.commands("Runtime.releaseObjectGroup").is_empty() }).await;
+        assert_eq!(
+            browser.commands("Runtime.releaseObjectGroup")[0].params["objectGroup"],
+            object_group
+        );
+    }
+
+    #[tokio::test]
+    async fn screenshot_hides_caret_only_while_capturing() {
+        let browser = MockBrowser::start(|method, params, session_id| match method {
+            "Page.captureScreenshot" => Ok(json!({ "data": "SU1H" })),
+            _ => default_reply(method, params, session_id),
+        })
+        .await;
+        let (_connection, _, frame_inner) = browser.page_frame().await;
+
+        let data = frame_inner
+            .screenshot(None, ScreenshotOptions::new().hide_caret(true).build())
+            .await
+            .unwrap();
+        assert_eq!(data, "SU1H");
+        let expressions: Vec<Value> = browser
+            .commands("Runtime.evaluate")
+            .into_iter()
+            .map(|command| command.params["expression"].clone())
+            .collect();
+        assert_eq!(
+            expressions,
+            vec![json!(HIDE_CARET_JS), json!(SHOW_CARET_JS)]
+        );
+    }
+
+    #[tokio::test]
+    async fn scroll_to_rejects_non_finite_positions() {
+        let browser = MockBrowser::start(default_reply).await;
+        let (_connection, _, frame_inner) = browser.page_frame().await;
+
+        assert!(frame_inner.scroll_to(f64::NAN, 0.0).await.is_err());
+        assert!(frame_inner.scroll_to(0.0, f64::INFINITY).await.is_err());
+        assert!(browser.commands("Runtime.evaluate").is_empty());
+    }
+
+    #[tokio::test]
+    async fn query_selector_all_stream_yields_nodes_that_fail_to_describe_as_errors() {
+        let browser = MockBrowser::start(|method, params, session_id| match method {
+            "DOM.resolveNode" => Ok(json!({ "object": { "type": "object", "objectId": "ROOT" } })),
+            "Runtime.callFunctionOn" => Ok(json!({ "result": { "type": "object", "subtype": "array", "objectId": "ROWS" } })),
+            "Runtime.getProperties" => Ok(json!({ "result": [
+                { "name": "0", "value": { "type": "object", "subtype": "node", "objectId": "ROW-0" }, "configurable": true, "enumerable": true },
+                { "name": "1", "value": { "type": "object", "subtype": "node", "objectId": "ROW-1" }, "configurable": true, "enumerable": true },
+            ] })),
+            "DOM.describeNode" if params["objectId"] == "ROW-0" => {
+                Err("Node has been detached".to_string())
+            }
+            "DOM.describeNode" => Ok(json!({ "node": { "backendNodeId": 11, "nodeType": 1 } })),
+            _ => default_reply(method, params, session_id),
+        })
+        .await;
+        let (_connection, _, frame_inner) = browser.page_frame().await;
+
+        let rows: Vec<Result<Element>> = frame_inner
+            .query_selector_all_stream("tr.row", Some(1))
+            .await
+            .unwrap()
+            .collect()
+            .await;
+        assert_eq!(rows.len(), 2);
+        assert!(rows[0].is_err());
+        assert_eq!(rows[1].as_ref().unwrap().backend_node_id(), 11);
+    }
+
+    #[tokio::test]
+    async fn query_selector_all_stream_reports_errors_of_step_queries() {
+        let browser = MockBrowser::start(|method, params, session_id| match method {
+            "DOM.getDocument" => Err("Document is not available".to_string()),
+            _ => default_reply(method, params, session_id),
+        })
+        .await;
+        let (_connection, _, frame_inner) = browser.page_frame().await;
+
+        assert!(
+            frame_inner
+                .query_selector_all_stream("ul > li", None)
+                .await
+                .is_err()
+        );
+        assert!(browser.commands("Runtime.callFunctionOn").is_empty());
+    }
+
+    #[tokio::test]
+    async fn init_script_and_evaluation_share_isolated_world() {
+        let browser = MockBrowser::start(|method, params, session_id| match method {
+            "Page.addScriptToEvaluateOnNewDocument" => Ok(json!({ "identifier": "1" })),
+            "Page.createIsolatedWorld" => Ok(json!({ "executionContextId": 9 })),
+            "Runtime.evaluate" => Ok(json!({ "result": { "type": "boolean", "value": true } })),
+            _ => default_reply(method, params, session_id),
+        })
+        .await;
+        let (_connection, _, frame_inner) = browser.page_frame().await;
+
+        frame_inner
+            .add_evaluate_on_new_document_in_world("globalThis.helper = true", "automation")
+            .await
+            .unwrap();
+        let value = frame_inner
+            .evaluate_in_world("automation", "globalThis.helper")
+            .await
+            .unwrap();
+
+        assert_eq!(value, json!(true));
+        let init_script = browser.commands("Page.addScriptToEvaluateOnNewDocument");
+        assert_eq!(init_script[0].params["worldName"], json!("automation"));
+        let world = browser.commands("Page.createIsolatedWorld");
+        assert_eq!(world[0].params["frameId"], json!("PAGE"));
+        assert_eq!(world[0].params["worldName"], json!("automation"));
+        let evaluation = browser.commands("Runtime.evaluate");
+        assert_eq!(evaluation.last().unwrap().params["contextId"], json!(9));
+    }
+
+    #[tokio::test]
+    async fn element_description_is_fetched_once() {
+        let browser = MockBrowser::start(|method, params, session_id| match method {
+            "DOM.describeNode" => Ok(json!({ "node": {
+                "backendNodeId": 5,
+                "nodeType": 1,
+                "nodeName": "BUTTON",
+                "localName": "button",
+                "nodeValue": "",
+                "attributes": ["class", "primary  large", "id", "submit"],
+            } })),
+            _ => default_reply(method, params, session_id),
+        })
+        .await;
+        let (_connection, _, frame_inner) = browser.page_frame().await;
+
+        let element = Element::new(Arc::downgrade(&frame_inner), 5);
+        assert_eq!(element.to_string(), "<node 5>");
+        let describes = browser.commands("DOM.describeNode").len();
+
+        assert_eq!(
+            element.describe().await.unwrap(),
+            "<button#submit.primary.large>"
+        );
+        assert_eq!(
+            element.clone().describe().await.unwrap(),
+            "<button#submit.primary.large>"
+        );
+        assert_eq!(browser.commands("DOM.describeNode").len(), describes + 1);
+        assert_eq!(element.to_string(), "<button#submit.primary.large>");
+    }
+
+    #[tokio::test]
+    async fn replays_recorded_input() {
+        let browser = MockBrowser::start(default_reply).await;
+        let (_connection, _, frame_inner) = browser.page_frame().await;
+
+        frame_inner.start_input_recording().await;
+        frame_inner
+            .send(
+                "Input.dispatchMouseEvent",
+                &DispatchMouseEvent::default(MouseEventType::MouseMoved, 10.0, 20.0),
+            )
+            .await
+            .unwrap();
+        frame_inner
+            .send("Input.insertText", &json!({ "text": "hi" }))
+            .await
+            .unwrap();
+        frame_inner.send("DOM.enable", &json!({})).await.unwrap();
+        let recording = frame_inner.stop_input_recording().await;
+        assert_eq!(recording.events.len(), 2);
+        assert_eq!(recording.events[0].params["x"], 10.0);
+
+        let recording = InputRecording::from_json(&recording.to_json().unwrap()).unwrap();
+        frame_inner.replay_input(&recording).await.unwrap();
+        assert_eq!(browser.commands("Input.dispatchMouseEvent").len(), 2);
+        assert_eq!(browser.commands("Input.insertText").len(), 2);
+        assert!(frame_inner.stop_input_recording().await.events.is_empty());
+    }
+
+    #[tokio::test]
+    async fn evaluate_as_deserializes_the_result() {
+        let browser = MockBrowser::start(|method, params, session_id| match method {
+            "Runtime.evaluate" => Ok(json!({ "result": { "type": "object", "value": [
+                { "name": "Lamp", "price": 20 },
+                { "name": "Desk", "price": 150 },
+            ] } })),
+            _ => default_reply(method, params, session_id),
+        })
+        .await;
+        let (_connection, _, frame_inner) = browser.page_frame().await;
+
+        #[derive(Debug, serde::Deserialize, PartialEq)]
+        struct Product {
+            name: String,
+            price: u32,
+        }
+        let products: Vec<Product> = frame_inner.evaluate_as("rows()").await.unwrap();
+        assert_eq!(products[1].name, "Desk");
+        assert_eq!(products[1].price, 150);
+
+        let error = frame_inner
+            .evaluate_as::<String>("rows()")
+            .await
+            .unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .starts_with("Evaluation result doesn't match")
+        );
+    }
+
+    #[tokio::test]
+    async fn check_visibility_passes_options_to_the_browser() {
+        let browser = MockBrowser::start(|method, params, session_id| match method {
+            "DOM.resolveNode" => Ok(json!({ "object": { "type": "object", "objectId": "NODE" } })),
+            "Runtime.callFunctionOn" => {
+                Ok(json!({ "result": { "type": "boolean", "value": false } }))
+            }
+            _ => default_reply(method, params, session_id),
+        })
+        .await;
+        let (_connection, _, frame_inner) = browser.page_frame().await;
+
+        let options = VisibilityOptions::default().check_opacity(true).build();
+        assert!(!frame_inner.check_visibility(&5, options).await.unwrap());
+
+        let called = browser.commands("Runtime.callFunctionOn");
+        assert_eq!(
+            called[0].params["functionDeclaration"],
+            json!(CHECK_VISIBILITY_JS)
+        );
+        assert_eq!(
+            called[0].params["arguments"][0]["value"],
+            json!({ "checkOpacity": true, "checkVisibilityCSS": false, "contentVisibilityAuto": false })
+        );
+    }
 }
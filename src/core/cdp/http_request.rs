@@ -9,6 +9,26 @@ use serde::Serialize;
 use std::collections::HashMap;
 use std::sync::Weak;
 
+///Request which ended with `Network.loadingFailed`: network error, CORS failure, blocked by the client or canceled.
+#[derive(Debug, Clone)]
+pub struct FailedRequest {
+    pub url: String,
+    pub resource_type: ResourceType,
+    pub error_text: String,
+    pub canceled: bool,
+    pub blocked_reason: Option<String>,
+}
+
+impl FailedRequest {
+    pub fn resource_kind(&self) -> ResourceKind {
+        ResourceKind::from(self.resource_type.as_str())
+    }
+
+    pub fn is_blocked(&self) -> bool {
+        self.blocked_reason.is_some()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct HttpRequest {
     connection: Weak<Connection>,
@@ -21,6 +41,7 @@ pub struct HttpRequest {
     method: String,
     headers: HashMap<String, String>,
     post_data: Option<Vec<PostDataEntry>>,
+    main_frame: bool,
 }
 
 impl HttpRequest {
@@ -55,9 +76,15 @@ impl HttpRequest {
             method: request.method,
             headers,
             post_data,
+            main_frame: false,
         }
     }
 
+    pub(crate) fn main_frame(mut self, main_frame: bool) -> Self {
+        self.main_frame = main_frame;
+        self
+    }
+
     async fn send<P: Serialize>(&self, method: &str, params: &P) -> Result<()> {
         let Some(conn) = self.connection.upgrade() else {
             return Err(anyhow!("Connection is not available"));
@@ -118,6 +145,20 @@ impl HttpRequest {
         &self.resource_type
     }
 
+    pub fn resource_kind(&self) -> ResourceKind {
+        ResourceKind::from(self.resource_type.as_str())
+    }
+
+    ///Request of the page's main frame, iframes and workers are excluded.
+    pub fn is_main_frame(&self) -> bool {
+        self.main_frame
+    }
+
+    ///Document request of the main frame, i.e. a page navigation. Iframe loads don't count.
+    pub fn is_navigation(&self) -> bool {
+        self.main_frame && self.resource_kind() == ResourceKind::Document
+    }
+
     pub fn url(&self) -> &str {
         &self.url
     }
@@ -11,6 +11,16 @@ use serde_json::Value;
 use std::collections::HashMap;
 use std::sync::Weak;
 
+///One redirect response on the way to the final response.
+#[derive(Debug, Clone)]
+pub struct RedirectHop {
+    pub url: String,
+    pub method: String,
+    pub status_code: i32,
+    pub status_text: String,
+    pub location: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct HttpResponse {
     connection: Weak<Connection>,
@@ -23,6 +33,7 @@ pub struct HttpResponse {
     url: String,
     method: String,
     response_body: Option<ResponseBody>,
+    redirect_chain: Vec<RedirectHop>,
 }
 
 impl HttpResponse {
@@ -31,6 +42,7 @@ impl HttpResponse {
         session_id: Weak<SessionId>,
         paused_request: RequestPaused,
         response_body: Option<ResponseBody>,
+        redirect_chain: Vec<RedirectHop>,
     ) -> Self {
         let request_id = paused_request.request_id;
         let resource_type = paused_request.resource_type;
@@ -50,6 +62,7 @@ impl HttpResponse {
             url,
             method,
             response_body,
+            redirect_chain,
         }
     }
 
@@ -108,6 +121,10 @@ impl HttpResponse {
         &self.resource_type
     }
 
+    pub fn resource_kind(&self) -> ResourceKind {
+        ResourceKind::from(self.resource_type.as_str())
+    }
+
     pub fn response_status_code(&self) -> i32 {
         self.response_status_code
     }
@@ -130,4 +147,28 @@ impl HttpResponse {
     pub fn method(&self) -> &str {
         &self.method
     }
+
+    pub fn is_redirect(&self) -> bool {
+        matches!(self.response_status_code, 301 | 302 | 303 | 307 | 308)
+    }
+
+    ///Redirects that led to this response, oldest first. Doesn't include the response itself.
+    pub fn redirect_chain(&self) -> &[RedirectHop] {
+        &self.redirect_chain
+    }
+
+    pub(crate) fn as_redirect_hop(&self) -> RedirectHop {
+        let location = self
+            .response_headers
+            .iter()
+            .find(|header| header.name.eq_ignore_ascii_case("location"))
+            .map(|header| header.value.clone());
+        RedirectHop {
+            url: self.url.clone(),
+            method: self.method.clone(),
+            status_code: self.response_status_code,
+            status_text: self.response_status_text.clone(),
+            location,
+        }
+    }
 }
@@ -0,0 +1,85 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Mutex;
+use tokio::time::{Duration, Instant};
+
+//Commands which change what the page receives as user input, everything else in the Input domain is configuration
+const RECORDED_METHODS: [&str; 5] = [
+    "Input.dispatchMouseEvent",
+    "Input.dispatchKeyEvent",
+    "Input.dispatchTouchEvent",
+    "Input.insertText",
+    "Input.synthesizeScrollGesture",
+];
+
+///One dispatched input command, `time` is milliseconds since the recording started.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedInput {
+    pub time: u64,
+    pub method: String,
+    pub params: Value,
+}
+
+///Inputs captured by `Page::start_input_recording`, serializable so a flaky sequence can be saved and replayed later.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InputRecording {
+    pub events: Vec<RecordedInput>,
+}
+
+impl InputRecording {
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    ///Recorded length, time of the last event.
+    pub fn duration(&self) -> Duration {
+        let last = self.events.last().map(|event| event.time).unwrap_or(0);
+        Duration::from_millis(last)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct InputRecorder {
+    recording: Mutex<Option<(Instant, Vec<RecordedInput>)>>,
+}
+
+impl InputRecorder {
+    ///Starts a new recording, events recorded so far are discarded.
+    pub fn start(&self) {
+        *self.recording.lock().unwrap() = Some((Instant::now(), Vec::new()));
+    }
+
+    pub fn stop(&self) -> InputRecording {
+        match self.recording.lock().unwrap().take() {
+            Some((_, events)) => InputRecording { events },
+            None => InputRecording::default(),
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording.lock().unwrap().is_some()
+    }
+
+    pub(crate) fn record<P: Serialize>(&self, method: &str, params: &P) {
+        if !RECORDED_METHODS.contains(&method) {
+            return;
+        }
+        let mut recording = self.recording.lock().unwrap();
+        let Some((started, events)) = recording.as_mut() else {
+            return;
+        };
+        let Ok(params) = serde_json::to_value(params) else {
+            return;
+        };
+        events.push(RecordedInput {
+            time: started.elapsed().as_millis() as u64,
+            method: method.to_string(),
+            params,
+        });
+    }
+}
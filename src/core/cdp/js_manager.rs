@@ -7,7 +7,7 @@ use anyhow::{Result, anyhow};
 use dashmap::DashSet;
 use serde::Serialize;
 use std::sync::{
-    Arc, Weak,
+    Arc, RwLock as StdRwLock, Weak,
     atomic::{AtomicBool, Ordering},
 };
 use tokio::sync::{RwLock, broadcast};
@@ -15,7 +15,8 @@ use tokio::task::JoinHandle;
 
 #[derive(Debug, Clone)]
 pub struct JsManager {
-    connection: Weak<Connection>,
+    //Swapped by `reinit` when the browser connection is rebuilt
+    connection: Arc<StdRwLock<Weak<Connection>>>,
     session_ids: DashSet<Arc<SessionId>>,
     js_dialog_sender: broadcast::Sender<JsDialog>,
     file_chooser_sender: broadcast::Sender<FileChooser>,
@@ -34,7 +35,7 @@ impl JsManager {
         let intercept_file_chooser = Arc::new(AtomicBool::new(false));
 
         Arc::new(Self {
-            connection,
+            connection: Arc::new(StdRwLock::new(connection)),
             session_ids,
             js_dialog_sender,
             file_chooser_sender,
@@ -46,7 +47,7 @@ impl JsManager {
     }
 
     pub fn connection(&self) -> Result<Arc<Connection>> {
-        match self.connection.upgrade() {
+        match self.connection.read().unwrap().upgrade() {
             Some(connection) => Ok(connection),
             None => Err(anyhow!("Connection is not available")),
         }
@@ -153,6 +154,22 @@ impl JsManager {
         }
     }
 
+    ///Moves the manager to a rebuilt connection: subscribes again and re-adds the sessions it had,
+    ///re-applying file chooser interception to them. Building block for reconnecting, which `Connection`
+    ///doesn't do on its own yet.
+    pub async fn reinit(self: Arc<Self>, connection: Weak<Connection>) -> Result<()> {
+        self.shutdown().await;
+        *self.connection.write().unwrap() = connection;
+        self.clone().init().await?;
+
+        let session_ids: Vec<Arc<SessionId>> =
+            self.session_ids.iter().map(|id| id.clone()).collect();
+        for session_id in session_ids {
+            self.add_session(session_id).await?;
+        }
+        Ok(())
+    }
+
     pub async fn add_session(&self, session_id: Arc<SessionId>) -> Result<()> {
         self.session_ids.insert(session_id.clone());
         let mut event_subscriber = self.event_subscriber.write().await;
@@ -0,0 +1,234 @@
+//Loopback CDP endpoint for the crate's own tests.
+//It speaks the same WebSocket protocol as Chrome, so Connection, TargetManager and the managers run unmodified on top of it.
+use super::connection::Connection;
+use super::frame_inner::FrameInner;
+use super::target_manager::TargetManager;
+use futures::{SinkExt, StreamExt};
+use serde_json::{Value, json};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio::time::Duration;
+use tokio_tungstenite::accept_async;
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+///Error message a handler returns to leave the command unanswered, like a browser stuck in a script.
+pub const NO_REPLY: &str = "chromatica-mock-no-reply";
+
+#[derive(Debug, Clone)]
+pub struct Command {
+    pub method: String,
+    pub params: Value,
+    pub session_id: Option<String>,
+}
+
+pub struct MockBrowser {
+    ws_url: String,
+    events: mpsc::UnboundedSender<Value>,
+    commands: Arc<Mutex<Vec<Command>>>,
+    handle: JoinHandle<()>,
+}
+
+impl MockBrowser {
+    ///`handler` gets method, params and session id of every command and returns its result or an error message.
+    pub async fn start<F>(handler: F) -> Self
+    where
+        F: Fn(&str, &Value, Option<&str>) -> Result<Value, String> + Send + Sync + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let ws_url = format!("ws://{}", listener.local_addr().unwrap());
+        let (events, mut events_rx) = mpsc::unbounded_channel::<Value>();
+        let commands = Arc::new(Mutex::new(Vec::new()));
+        let commands_clone = commands.clone();
+
+        let handle = tokio::spawn(async move {
+            let Ok((stream, _)) = listener.accept().await else {
+                return;
+            };
+            let Ok(ws_stream) = accept_async(stream).await else {
+                return;
+            };
+            let (mut ws_sink, mut ws_stream) = ws_stream.split();
+
+            loop {
+                tokio::select! {
+                    message = ws_stream.next() => {
+                        let text = match message {
+                            Some(Ok(WsMessage::Text(text))) => text,
+                            Some(Ok(_)) => continue,
+                            _ => break,
+                        };
+                        let request: Value = serde_json::from_str(&text).unwrap();
+                        let method = request["method"].as_str().unwrap_or_default().to_string();
+                        let params = request.get("params").cloned().unwrap_or(Value::Null);
+                        let session_id = request
+                            .get("sessionId")
+                            .and_then(|session_id| session_id.as_str())
+                            .map(|session_id| session_id.to_string());
+
+                        let result = handler(&method, &params, session_id.as_deref());
+                        commands_clone.lock().unwrap().push(Command {
+                            method,
+                            params,
+                            session_id: session_id.clone(),
+                        });
+
+                        let mut response = match result {
+                            Err(message) if message == NO_REPLY => continue,
+                            Ok(result) => json!({ "id": request["id"], "result": result }),
+                            Err(message) => json!({
+                                "id": request["id"],
+                                "error": { "code": -32000, "message": message }
+                            }),
+                        };
+                        if let Some(session_id) = &session_id {
+                            response["sessionId"] = json!(session_id);
+                        }
+
+                        if ws_sink
+                            .send(WsMessage::Text(response.to_string().into()))
+                            .await
+                            .is_err()
+                        {
+                            break;
+                        }
+                    }
+                    event = events_rx.recv() => {
+                        let Some(event) = event else {
+                            break;
+                        };
+                        if ws_sink
+                            .send(WsMessage::Text(event.to_string().into()))
+                            .await
+                            .is_err()
+                        {
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+
+        Self {
+            ws_url,
+            events,
+            commands,
+            handle,
+        }
+    }
+
+    pub fn ws_url(&self) -> &str {
+        &self.ws_url
+    }
+
+    pub async fn connect(&self) -> Arc<Connection> {
+        Connection::connect(&self.ws_url).await.unwrap()
+    }
+
+    ///Connects and starts target discovery, like `Chromatica::connect` does.
+    pub async fn connect_with_targets(&self) -> (Arc<Connection>, Arc<TargetManager>) {
+        let connection = self.connect().await;
+        let target_manager = connection.target_manager().unwrap().clone();
+        target_manager.clone().init().await.unwrap();
+        (connection, target_manager)
+    }
+
+    ///Connects with target discovery and attaches a "PAGE" page target, the usual start of a page test.
+    pub async fn page_frame(&self) -> (Arc<Connection>, Arc<TargetManager>, Arc<FrameInner>) {
+        let (connection, target_manager) = self.connect_with_targets().await;
+        self.emit_target_created("PAGE", "page");
+        let page_id = "PAGE".to_string();
+        eventually(|| async { target_manager.get_frame_inner(&page_id).await.is_some() }).await;
+        let frame_inner = target_manager.get_frame_inner(&page_id).await.unwrap();
+        (connection, target_manager, frame_inner)
+    }
+
+    pub fn emit(&self, method: &str, params: Value, session_id: Option<&str>) {
+        let mut event = json!({ "method": method, "params": params });
+        if let Some(session_id) = session_id {
+            event["sessionId"] = json!(session_id);
+        }
+        let _ = self.events.send(event);
+    }
+
+    pub fn emit_target_created(&self, target_id: &str, target_type: &str) {
+        self.emit(
+            "Target.targetCreated",
+            json!({
+                "targetInfo": {
+                    "targetId": target_id,
+                    "type": target_type,
+                    "title": "",
+                    "url": "about:blank",
+                    "attached": false,
+                }
+            }),
+            None,
+        );
+    }
+
+    pub fn commands(&self, method: &str) -> Vec<Command> {
+        self.commands
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|command| command.method == method)
+            .cloned()
+            .collect()
+    }
+}
+
+impl Drop for MockBrowser {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+pub fn session_for(target_id: &str) -> String {
+    format!("session-{}", target_id)
+}
+
+pub fn target_for(session_id: &str) -> &str {
+    session_id.strip_prefix("session-").unwrap_or(session_id)
+}
+
+pub fn frame_tree(frame_id: &str, parent_id: Option<&str>) -> Value {
+    let mut frame = json!({ "id": frame_id, "url": "about:blank" });
+    if let Some(parent_id) = parent_id {
+        frame["parentId"] = json!(parent_id);
+    }
+    json!({ "frameTree": { "frame": frame } })
+}
+
+///Replies good enough to attach targets and initialize the managers, everything else gets an empty result.
+pub fn default_reply(
+    method: &str,
+    params: &Value,
+    session_id: Option<&str>,
+) -> Result<Value, String> {
+    match method {
+        "Target.attachToTarget" => {
+            let target_id = params["targetId"].as_str().unwrap_or_default();
+            Ok(json!({ "sessionId": session_for(target_id) }))
+        }
+        "Page.getFrameTree" => Ok(frame_tree(target_for(session_id.unwrap_or_default()), None)),
+        _ => Ok(json!({})),
+    }
+}
+
+///Polls `condition` until it holds, panics after a few seconds.
+pub async fn eventually<F, Fut>(mut condition: F)
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = bool>,
+{
+    let result = tokio::time::timeout(Duration::from_secs(5), async {
+        while !condition().await {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await;
+    assert!(result.is_ok(), "Condition was not met in time");
+}
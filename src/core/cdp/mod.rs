@@ -1,6 +1,8 @@
 pub mod browser;
 pub mod browser_context;
 pub mod connection;
+pub mod console;
+mod cookie_jar;
 pub mod domains;
 pub mod element;
 pub mod emulation_manager;
@@ -9,10 +11,15 @@ pub mod frame_inner;
 pub mod http_request;
 pub mod http_response;
 pub mod iframe;
+pub mod input_recorder;
 pub mod js_dialogs;
 pub mod js_manager;
+#[cfg(test)]
+pub(crate) mod mock;
 pub mod network_manager;
 pub mod page;
 mod query_builder;
 pub mod target;
 pub mod target_manager;
+pub mod web_vitals;
+pub mod worker;
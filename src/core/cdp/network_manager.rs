@@ -1,16 +1,19 @@
 use super::connection::{Connection, EventParams, EventSubscriber, Response as CdpResponse};
+use super::domains::fetch::RequestId as FetchRequestId;
 use super::domains::fetch::*;
+use super::domains::network::RequestId as NetworkRequestId;
 use super::domains::network::*;
+use super::domains::page::FrameId;
 use super::domains::target::*;
-use super::http_request::HttpRequest;
-use super::http_response::HttpResponse;
+use super::http_request::{FailedRequest, HttpRequest};
+use super::http_response::{HttpResponse, RedirectHop};
 use anyhow::{Result, anyhow};
 use dashmap::{DashMap, DashSet};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 // use std::collections::HashSet;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Arc, Weak};
+use std::sync::{Arc, RwLock as StdRwLock, Weak};
 use tokio::sync::{RwLock, broadcast};
 use tokio::task::JoinHandle;
 
@@ -84,9 +87,14 @@ impl Drop for ResponseStream {
     }
 }
 
+//Network request id of the request the hops lead to, so the chain can be dropped when it fails
+type RedirectChain = (Option<NetworkRequestId>, Vec<RedirectHop>);
+
 #[derive(Debug, Clone)]
 pub struct NetworkManager {
-    connection: Weak<Connection>,
+    //Swapped by `reinit` when the browser connection is rebuilt
+    connection: Arc<StdRwLock<Weak<Connection>>>,
+    main_frame_id: Arc<FrameId>,
     session_ids: DashSet<Arc<SessionId>>,
     network_handler: Arc<AtomicBool>,
     credentials: Arc<RwLock<Option<Credentials>>>,
@@ -97,16 +105,24 @@ pub struct NetworkManager {
     extra_headers: DashMap<String, String>,
     cache_disabled: Arc<AtomicBool>,
     bypass_service_worker: Arc<AtomicBool>,
+    //Redirect hops collected so far, keyed by Fetch request id of the request they lead to
+    redirects: Arc<DashMap<(SessionId, FetchRequestId), RedirectChain>>,
+    //Url and resource type of Network requests which haven't finished yet, loadingFailed carries neither
+    requests_in_flight: Arc<DashMap<(SessionId, NetworkRequestId), (String, ResourceType)>>,
+    failed_request_sender: broadcast::Sender<FailedRequest>,
     //Something with cookies
 }
 
 impl NetworkManager {
-    pub fn new(connection: Weak<Connection>) -> Arc<Self> {
+    ///`main_frame_id` is the frame of the top level target, its document requests are navigations.
+    pub fn new(connection: Weak<Connection>, main_frame_id: Arc<FrameId>) -> Arc<Self> {
         let (request_sender, _) = broadcast::channel(1024);
         let (response_sender, _) = broadcast::channel(1024);
+        let (failed_request_sender, _) = broadcast::channel(1024);
 
         Arc::new(Self {
-            connection,
+            connection: Arc::new(StdRwLock::new(connection)),
+            main_frame_id,
             session_ids: DashSet::with_capacity(4),
             network_handler: Arc::new(AtomicBool::new(false)),
             credentials: Arc::new(RwLock::new(None)),
@@ -117,11 +133,14 @@ impl NetworkManager {
             extra_headers: DashMap::new(),
             cache_disabled: Arc::new(AtomicBool::new(false)),
             bypass_service_worker: Arc::new(AtomicBool::new(false)),
+            redirects: Arc::new(DashMap::new()),
+            requests_in_flight: Arc::new(DashMap::new()),
+            failed_request_sender,
         })
     }
 
     fn connection(&self) -> Option<Arc<Connection>> {
-        match self.connection.upgrade() {
+        match self.connection.read().unwrap().upgrade() {
             Some(conn) => Some(conn),
             None => None,
         }
@@ -151,9 +170,12 @@ impl NetworkManager {
 
         let network_manager_downgraded = Arc::downgrade(&self);
 
-        let methods = DashSet::with_capacity(2);
+        let methods = DashSet::with_capacity(5);
         methods.insert("Fetch.authRequired".to_string());
         methods.insert("Fetch.requestPaused".to_string());
+        methods.insert("Network.requestWillBeSent".to_string());
+        methods.insert("Network.loadingFinished".to_string());
+        methods.insert("Network.loadingFailed".to_string());
 
         let session_ids = DashSet::with_capacity(4);
 
@@ -190,6 +212,20 @@ impl NetworkManager {
                                 .await;
                         }
                     }
+                    EventParams::RequestWillBeSent(request_will_be_sent) => {
+                        let session_id = event.session_id.as_ref().unwrap();
+                        network_manager.on_request_will_be_sent(session_id, request_will_be_sent);
+                    }
+                    EventParams::LoadingFinished(loading_finished) => {
+                        let session_id = event.session_id.as_ref().unwrap();
+                        network_manager
+                            .requests_in_flight
+                            .remove(&(session_id.clone(), loading_finished.request_id.clone()));
+                    }
+                    EventParams::LoadingFailed(loading_failed) => {
+                        let session_id = event.session_id.as_ref().unwrap();
+                        network_manager.on_loading_failed(session_id, loading_failed);
+                    }
                     _ => {}
                 }
                 tokio::task::yield_now().await;
@@ -205,6 +241,26 @@ impl NetworkManager {
         Ok(())
     }
 
+    ///Moves the manager to a rebuilt connection: subscribes again and re-adds the sessions it had,
+    ///re-applying headers, cache and service worker settings to them. Building block for reconnecting,
+    ///which `Connection` doesn't do on its own yet.
+    pub async fn reinit(self: Arc<Self>, connection: Weak<Connection>) -> Result<()> {
+        self.shutdown().await;
+        *self.connection.write().unwrap() = connection;
+        //Requests of the old connection never finish
+        self.requests_in_flight.clear();
+        self.redirects.clear();
+        self.clone().init().await?;
+
+        let session_ids: Vec<Arc<SessionId>> =
+            self.session_ids.iter().map(|id| id.clone()).collect();
+        self.session_ids.clear();
+        for session_id in session_ids {
+            self.add_session(session_id).await?;
+        }
+        Ok(())
+    }
+
     pub async fn add_session(&self, session_id: Arc<SessionId>) -> Result<()> {
         if !self.session_ids.contains(&session_id) {
             self.session_ids.insert(session_id.clone());
@@ -256,6 +312,11 @@ impl NetworkManager {
 
     pub async fn remove_session(&self, session_id: &Arc<SessionId>) {
         self.session_ids.remove(session_id);
+        //Destroyed targets never finish their requests
+        self.requests_in_flight
+            .retain(|(session, _), _| session != session_id.as_ref());
+        self.redirects
+            .retain(|(session, _), _| session != session_id.as_ref());
         let mut event_subscriber = self.event_subscriber.write().await;
         if let Some(event_subscriber) = event_subscriber.as_mut() {
             if let Some(event_subscriber) = event_subscriber.upgrade() {
@@ -280,6 +341,18 @@ impl NetworkManager {
             return Err(anyhow!("Connection is not available"));
         };
 
+        //Chrome pauses redirected request with new id, so carry the chain over
+        if let Some(redirected_request_id) = &request_paused.redirected_request_id
+            && let Some((_, (_, chain))) = self
+                .redirects
+                .remove(&(session_id.clone(), redirected_request_id.clone()))
+        {
+            self.redirects.insert(
+                (session_id.clone(), request_paused.request_id.clone()),
+                (request_paused.network_id.clone(), chain),
+            );
+        }
+
         let session_id = match self.session_ids.get(session_id) {
             Some(session_id) => Arc::downgrade(&session_id),
             None => return Err(anyhow!("Session id is not available")),
@@ -295,7 +368,8 @@ impl NetworkManager {
             request_paused.resource_type.clone(),
             request_paused.request.clone(),
             None,
-        );
+        )
+        .main_frame(request_paused.frame_id.as_deref() == Some(self.main_frame_id.as_str()));
 
         if !self.network_handler.load(Ordering::SeqCst) {
             match request.continue_request().await {
@@ -309,6 +383,44 @@ impl NetworkManager {
         Ok(())
     }
 
+    fn on_request_will_be_sent(
+        &self,
+        session_id: &SessionId,
+        request_will_be_sent: &RequestWillBeSent,
+    ) {
+        let resource_type = request_will_be_sent
+            .resource_type
+            .clone()
+            .unwrap_or_else(|| "Other".to_string());
+        //Redirects reuse the request id, the entry is just overwritten with the new url
+        self.requests_in_flight.insert(
+            (session_id.clone(), request_will_be_sent.request_id.clone()),
+            (request_will_be_sent.request.url.clone(), resource_type),
+        );
+    }
+
+    fn on_loading_failed(&self, session_id: &SessionId, loading_failed: &LoadingFailed) {
+        //The follow up of a redirect failed or was aborted, its response never comes
+        self.redirects.retain(|(session, _), (network_id, _)| {
+            session != session_id || network_id.as_ref() != Some(&loading_failed.request_id)
+        });
+        let url = match self
+            .requests_in_flight
+            .remove(&(session_id.clone(), loading_failed.request_id.clone()))
+        {
+            Some((_, (url, _))) => url,
+            None => String::new(),
+        };
+        let failed_request = FailedRequest {
+            url,
+            resource_type: loading_failed.recource_type.clone(),
+            error_text: loading_failed.error_text.clone(),
+            canceled: loading_failed.canceled.unwrap_or(false),
+            blocked_reason: loading_failed.blocked_reason.clone(),
+        };
+        let _ = self.failed_request_sender.send(failed_request);
+    }
+
     pub async fn on_auth_required(
         &self,
         session_id: &SessionId,
@@ -335,7 +447,8 @@ impl NetworkManager {
             auth_required.resource_type.clone(),
             auth_required.request.clone(),
             credentials,
-        );
+        )
+        .main_frame(auth_required.frame_id == *self.main_frame_id);
 
         if !self.network_handler.load(Ordering::SeqCst) {
             match request.continue_request().await {
@@ -386,13 +499,29 @@ impl NetworkManager {
             }
         };
 
+        let redirect_chain = self
+            .redirects
+            .remove(&(session_id.to_string(), request_paused.request_id.clone()))
+            .map(|(_, (_, chain))| chain)
+            .unwrap_or_default();
+
         let response = HttpResponse::new(
             downgraded_conn,
             Arc::downgrade(&session_id),
             request_paused.clone(),
             response_body,
+            redirect_chain,
         );
 
+        if is_redirect {
+            let mut chain = response.redirect_chain().to_vec();
+            chain.push(response.as_redirect_hop());
+            self.redirects.insert(
+                (session_id.to_string(), request_paused.request_id.clone()),
+                (request_paused.network_id.clone(), chain),
+            );
+        }
+
         if !self.network_handler.load(Ordering::SeqCst) {
             match response.continue_response().await {
                 Ok(_) => (),
@@ -413,6 +542,20 @@ impl NetworkManager {
         ResponseStream::new(self.response_sender.subscribe(), self.clone())
     }
 
+    ///Receiver which doesn't own interception state, so dropping it won't turn off user's handlers.
+    pub fn observe_responses(&self) -> broadcast::Receiver<HttpResponse> {
+        self.response_sender.subscribe()
+    }
+
+    pub fn subscribe_to_failed_requests(&self) -> broadcast::Receiver<FailedRequest> {
+        self.failed_request_sender.subscribe()
+    }
+
+    ///Requests seen in `Network.requestWillBeSent` which haven't finished or failed yet.
+    pub fn pending_request_count(&self) -> usize {
+        self.requests_in_flight.len()
+    }
+
     pub fn set_request_interception(&self, enabled: bool) {
         self.network_handler.store(enabled, Ordering::SeqCst);
     }
@@ -449,6 +592,39 @@ impl NetworkManager {
         Ok(())
     }
 
+    ///Adds or replaces one header, the rest of the set is kept. Header names are case-insensitive.
+    pub async fn add_extra_header(&self, name: &str, value: &str) -> Result<()> {
+        self.extra_headers
+            .retain(|key, _| !key.eq_ignore_ascii_case(name));
+        self.extra_headers
+            .insert(name.to_string(), value.to_string());
+        self.send_extra_headers().await
+    }
+
+    pub async fn remove_extra_header(&self, name: &str) -> Result<()> {
+        self.extra_headers
+            .retain(|key, _| !key.eq_ignore_ascii_case(name));
+        self.send_extra_headers().await
+    }
+
+    async fn send_extra_headers(&self) -> Result<()> {
+        let headers: HashMap<String, String> = self
+            .extra_headers
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().clone()))
+            .collect();
+
+        for session_id in self.session_ids.iter() {
+            self.send(
+                "Network.setExtraHTTPHeaders",
+                &NetworkSetExtraHTTPHeaders::default(&headers),
+                Some(&session_id),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
     pub async fn clear_extra_headers(&self) -> Result<()> {
         for session_id in self.session_ids.iter() {
             self.send(
@@ -489,3 +665,324 @@ impl NetworkManager {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::super::mock::{MockBrowser, eventually};
+    use super::*;
+    use serde_json::{Value, json};
+
+    fn request_paused(request_id: &str) -> Value {
+        json!({
+            "requestId": request_id,
+            "request": {
+                "url": "https://example.com/",
+                "method": "GET",
+                "headers": {},
+            },
+            "frameId": "PAGE",
+            "resourceType": "Document",
+        })
+    }
+
+    async fn network_manager(browser: &MockBrowser) -> (Arc<Connection>, Arc<NetworkManager>) {
+        let connection = browser.connect().await;
+        let network_manager =
+            NetworkManager::new(Arc::downgrade(&connection), Arc::new("PAGE".to_string()));
+        network_manager.clone().init().await.unwrap();
+        network_manager
+            .add_session(Arc::new("session-PAGE".to_string()))
+            .await
+            .unwrap();
+        (connection, network_manager)
+    }
+
+    #[tokio::test]
+    async fn continues_paused_requests_without_interception() {
+        let browser = MockBrowser::start(|_, _, _| Ok(json!({}))).await;
+        let (_connection, _network_manager) = network_manager(&browser).await;
+
+        browser.emit(
+            "Fetch.requestPaused",
+            request_paused("other"),
+            Some("session-OTHER"),
+        );
+        browser.emit(
+            "Fetch.requestPaused",
+            request_paused("1"),
+            Some("session-PAGE"),
+        );
+
+        eventually(|| async { !browser.commands("Fetch.continueRequest").is_empty() }).await;
+        let continued = browser.commands("Fetch.continueRequest");
+        assert_eq!(continued.len(), 1);
+        assert_eq!(continued[0].params["requestId"], "1");
+        assert_eq!(continued[0].session_id.as_deref(), Some("session-PAGE"));
+    }
+
+    #[tokio::test]
+    async fn only_main_frame_documents_are_navigations() {
+        let browser = MockBrowser::start(|_, _, _| Ok(json!({}))).await;
+        let (_connection, network_manager) = network_manager(&browser).await;
+
+        let mut requests = network_manager.clone().subscribe_to_requests();
+        network_manager.set_request_interception(true);
+        let mut iframe = request_paused("2");
+        iframe["frameId"] = json!("CHILD");
+        for request in [request_paused("1"), iframe] {
+            browser.emit("Fetch.requestPaused", request, Some("session-PAGE"));
+        }
+
+        let timeout = tokio::time::Duration::from_secs(5);
+        let main = tokio::time::timeout(timeout, requests.next())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(main.is_main_frame());
+        assert!(main.is_navigation());
+        let iframe = tokio::time::timeout(timeout, requests.next())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(iframe.resource_type(), "Document");
+        assert!(!iframe.is_navigation());
+    }
+
+    #[tokio::test]
+    async fn hands_paused_requests_to_interceptor() {
+        let browser = MockBrowser::start(|_, _, _| Ok(json!({}))).await;
+        let (_connection, network_manager) = network_manager(&browser).await;
+
+        let mut requests = network_manager.clone().subscribe_to_requests();
+        network_manager.set_request_interception(true);
+        browser.emit(
+            "Fetch.requestPaused",
+            request_paused("1"),
+            Some("session-PAGE"),
+        );
+
+        let request = tokio::time::timeout(tokio::time::Duration::from_secs(5), requests.next())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(request.url(), "https://example.com/");
+        assert_eq!(request.resource_type(), "Document");
+        assert!(browser.commands("Fetch.continueRequest").is_empty());
+
+        request.continue_request().await.unwrap();
+        assert_eq!(browser.commands("Fetch.continueRequest").len(), 1);
+
+        drop(requests);
+        assert!(!network_manager.network_handler.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn observing_responses_keeps_interception_state() {
+        let browser = MockBrowser::start(|_, _, _| Ok(json!({}))).await;
+        let (_connection, network_manager) = network_manager(&browser).await;
+
+        network_manager.set_request_interception(true);
+        let responses = network_manager.observe_responses();
+        drop(responses);
+        assert!(network_manager.network_handler.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn collects_redirect_chain_across_request_ids() {
+        let browser = MockBrowser::start(|method, _, _| match method {
+            "Fetch.getResponseBody" => Ok(json!({ "body": "ok", "base64Encoded": false })),
+            _ => Ok(json!({})),
+        })
+        .await;
+        let (_connection, network_manager) = network_manager(&browser).await;
+        let mut responses = network_manager.observe_responses();
+
+        let mut redirect = request_paused("1");
+        redirect["responseStatusCode"] = json!(302);
+        redirect["responseStatusText"] = json!("Found");
+        redirect["responseHeaders"] = json!([{ "name": "Location", "value": "/login" }]);
+        browser.emit("Fetch.requestPaused", redirect, Some("session-PAGE"));
+
+        let mut follow_up = request_paused("2");
+        follow_up["redirectedRequestId"] = json!("1");
+        follow_up["request"]["url"] = json!("https://example.com/login");
+        browser.emit(
+            "Fetch.requestPaused",
+            follow_up.clone(),
+            Some("session-PAGE"),
+        );
+
+        follow_up["responseStatusCode"] = json!(200);
+        follow_up["responseStatusText"] = json!("OK");
+        follow_up["responseHeaders"] = json!([]);
+        browser.emit("Fetch.requestPaused", follow_up, Some("session-PAGE"));
+
+        let first = responses.recv().await.unwrap();
+        assert!(first.is_redirect());
+        assert!(first.redirect_chain().is_empty());
+
+        let last = responses.recv().await.unwrap();
+        assert_eq!(last.url(), "https://example.com/login");
+        let chain = last.redirect_chain();
+        assert_eq!(chain.len(), 1);
+        assert_eq!(chain[0].url, "https://example.com/");
+        assert_eq!(chain[0].status_code, 302);
+        assert_eq!(chain[0].location.as_deref(), Some("/login"));
+        assert!(network_manager.redirects.is_empty());
+    }
+
+    #[tokio::test]
+    async fn drops_redirect_chain_when_follow_up_fails() {
+        let browser = MockBrowser::start(|_, _, _| Ok(json!({}))).await;
+        let (_connection, network_manager) = network_manager(&browser).await;
+        let mut failed_requests = network_manager.subscribe_to_failed_requests();
+
+        for (request_id, network_id) in [("1", "N1"), ("2", "N2")] {
+            let mut redirect = request_paused(request_id);
+            redirect["networkId"] = json!(network_id);
+            redirect["responseStatusCode"] = json!(302);
+            redirect["responseStatusText"] = json!("Found");
+            redirect["responseHeaders"] = json!([{ "name": "Location", "value": "/login" }]);
+            browser.emit("Fetch.requestPaused", redirect, Some("session-PAGE"));
+        }
+        eventually(|| async { network_manager.redirects.len() == 2 }).await;
+
+        browser.emit(
+            "Network.loadingFailed",
+            json!({ "requestId": "N1", "errorText": "net::ERR_FAILED", "type": "Document" }),
+            Some("session-PAGE"),
+        );
+        failed_requests.recv().await.unwrap();
+        assert_eq!(network_manager.redirects.len(), 1);
+
+        network_manager
+            .remove_session(&Arc::new("session-PAGE".to_string()))
+            .await;
+        assert!(network_manager.redirects.is_empty());
+    }
+
+    #[tokio::test]
+    async fn reports_failed_requests_with_their_url() {
+        let browser = MockBrowser::start(|_, _, _| Ok(json!({}))).await;
+        let (_connection, network_manager) = network_manager(&browser).await;
+        let mut failed_requests = network_manager.subscribe_to_failed_requests();
+
+        for request_id in ["1", "2"] {
+            let mut request_will_be_sent = request_paused(request_id);
+            request_will_be_sent["request"]["url"] =
+                json!(format!("https://example.com/{}.png", request_id));
+            request_will_be_sent["type"] = json!("Image");
+            browser.emit(
+                "Network.requestWillBeSent",
+                request_will_be_sent,
+                Some("session-PAGE"),
+            );
+        }
+        browser.emit(
+            "Network.loadingFinished",
+            json!({ "requestId": "1" }),
+            Some("session-PAGE"),
+        );
+        browser.emit(
+            "Network.loadingFailed",
+            json!({
+                "requestId": "2",
+                "errorText": "net::ERR_BLOCKED_BY_CLIENT",
+                "type": "Image",
+                "blockedReason": "inspector",
+            }),
+            Some("session-PAGE"),
+        );
+
+        let failed_request = failed_requests.recv().await.unwrap();
+        assert_eq!(failed_request.url, "https://example.com/2.png");
+        assert_eq!(failed_request.resource_type, "Image");
+        assert_eq!(failed_request.error_text, "net::ERR_BLOCKED_BY_CLIENT");
+        assert!(failed_request.is_blocked());
+        assert!(!failed_request.canceled);
+        assert!(network_manager.requests_in_flight.is_empty());
+    }
+
+    #[tokio::test]
+    async fn merges_single_extra_header_into_existing_set() {
+        let browser = MockBrowser::start(|_, _, _| Ok(json!({}))).await;
+        let (_connection, network_manager) = network_manager(&browser).await;
+
+        network_manager
+            .set_extra_headers(HashMap::from([("X-Static", "1"), ("Authorization", "old")]))
+            .await
+            .unwrap();
+        network_manager
+            .add_extra_header("authorization", "new")
+            .await
+            .unwrap();
+        network_manager
+            .remove_extra_header("x-static")
+            .await
+            .unwrap();
+
+        let sent = browser.commands("Network.setExtraHTTPHeaders");
+        assert_eq!(sent.len(), 3);
+        assert_eq!(
+            sent[1].params["headers"],
+            json!({ "X-Static": "1", "authorization": "new" })
+        );
+        assert_eq!(sent[2].params["headers"], json!({ "authorization": "new" }));
+    }
+
+    #[tokio::test]
+    async fn reinit_resubscribes_sessions_on_new_connection() {
+        let browser = MockBrowser::start(|_, _, _| Ok(json!({}))).await;
+        let (_connection, network_manager) = network_manager(&browser).await;
+        network_manager
+            .add_extra_header("x-test", "1")
+            .await
+            .unwrap();
+
+        let reconnected = MockBrowser::start(|_, _, _| Ok(json!({}))).await;
+        let connection = reconnected.connect().await;
+        network_manager
+            .clone()
+            .reinit(Arc::downgrade(&connection))
+            .await
+            .unwrap();
+        let headers = reconnected.commands("Network.setExtraHTTPHeaders");
+        assert_eq!(headers.len(), 1);
+        assert_eq!(headers[0].session_id.as_deref(), Some("session-PAGE"));
+
+        reconnected.emit(
+            "Fetch.requestPaused",
+            request_paused("1"),
+            Some("session-PAGE"),
+        );
+        eventually(|| async { !reconnected.commands("Fetch.continueRequest").is_empty() }).await;
+        assert!(browser.commands("Fetch.continueRequest").is_empty());
+    }
+
+    #[tokio::test]
+    async fn counts_pending_requests() {
+        let browser = MockBrowser::start(|_, _, _| Ok(json!({}))).await;
+        let (_connection, network_manager) = network_manager(&browser).await;
+
+        for request_id in ["1", "2"] {
+            browser.emit(
+                "Network.requestWillBeSent",
+                request_paused(request_id),
+                Some("session-PAGE"),
+            );
+        }
+        eventually(|| async { network_manager.pending_request_count() == 2 }).await;
+
+        browser.emit(
+            "Network.loadingFinished",
+            json!({ "requestId": "1" }),
+            Some("session-PAGE"),
+        );
+        eventually(|| async { network_manager.pending_request_count() == 1 }).await;
+
+        network_manager
+            .remove_session(&Arc::new("session-PAGE".to_string()))
+            .await;
+        assert_eq!(network_manager.pending_request_count(), 0);
+    }
+}
@@ -1,29 +1,96 @@
+use super::browser_context::BrowserContextHandle;
+use super::console::{ConsoleStream, PageErrorStream};
+use super::domains::browser::DownloadBehavior;
+use super::domains::dom::BackendNodeId;
 use super::domains::page::{PrintToPDF, ScriptIdentifier};
+use super::domains::web_authn::{AuthenticatorId, Credential, VirtualAuthenticatorOptions};
 use super::element::Element;
 use super::frame_inner::FrameInner;
+use super::http_request::FailedRequest;
 use super::http_response::HttpResponse;
+use super::input_recorder::InputRecording;
 use super::js_dialogs::JsDialog;
 use super::network_manager::{RequestStream, ResponseStream};
+use super::web_vitals::WebVitals;
 
-use super::emulation_manager::UserAgentOverride;
+use super::emulation_manager::{
+    Country, DeviceMetrics, Geolocation, MediaEmulationGuard, UserAgentOverride,
+};
 use anyhow::{Result, anyhow};
+use futures::stream::BoxStream;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
 use std::collections::HashMap;
 use std::sync::{Arc, Weak};
 use tokio::sync::broadcast;
 use tokio::time::Duration;
 
+///Screenshot settings for `screenshot_with`. `quality` (0-100) is accepted only with `jpeg` or `webp` format,
+///`hide_caret` makes the text caret transparent while capturing, so focused inputs don't make screenshots flaky.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ScreenshotOptions<'a> {
+    pub save_path: Option<&'a str>,
+    pub format: Option<&'a str>,
+    pub quality: Option<u64>,
+    pub full_page: Option<bool>,
+    pub hide_caret: bool,
+}
+
+impl<'a> ScreenshotOptions<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn save_path(mut self, save_path: &'a str) -> Self {
+        self.save_path = Some(save_path);
+        self
+    }
+
+    pub fn format(mut self, format: &'a str) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    pub fn quality(mut self, quality: u64) -> Self {
+        self.quality = Some(quality);
+        self
+    }
+
+    pub fn full_page(mut self, full_page: bool) -> Self {
+        self.full_page = Some(full_page);
+        self
+    }
+
+    pub fn hide_caret(mut self, hide_caret: bool) -> Self {
+        self.hide_caret = hide_caret;
+        self
+    }
+
+    pub fn build(self) -> Self {
+        self
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Page {
     frame_inner: Weak<FrameInner>,
+    //Keeps the browser context the page was created in from being disposed
+    context_handle: Option<Arc<BrowserContextHandle>>,
 }
 
 impl Page {
     pub fn new(frame_inner: Arc<FrameInner>) -> Self {
         Self {
             frame_inner: Arc::downgrade(&frame_inner),
+            context_handle: None,
         }
     }
 
+    pub(crate) fn with_context_handle(mut self, context_handle: Arc<BrowserContextHandle>) -> Self {
+        self.context_handle = Some(context_handle);
+        self
+    }
+
     fn frame_inner(&self) -> Option<Arc<FrameInner>> {
         match self.frame_inner.upgrade() {
             Some(frame_inner) => Some(frame_inner),
@@ -59,6 +126,42 @@ impl Page {
         }
     }
 
+    pub async fn session_id(&self) -> Result<String> {
+        match self.frame_inner() {
+            Some(frame_inner) => Ok(frame_inner.session_id().await),
+            None => Err(anyhow!("Frame inner is dropped")),
+        }
+    }
+
+    pub async fn target_id(&self) -> Result<String> {
+        match self.frame_inner() {
+            Some(frame_inner) => Ok(frame_inner.target_id().await),
+            None => Err(anyhow!("Frame inner is dropped")),
+        }
+    }
+
+    pub async fn evaluate(&self, expression: &str, timeout: Option<Duration>) -> Result<Value> {
+        match self.frame_inner() {
+            Some(frame_inner) => frame_inner.evaluate(expression, timeout).await,
+            None => Err(anyhow!("Frame inner is dropped")),
+        }
+    }
+
+    pub async fn evaluate_as<T: DeserializeOwned>(&self, expression: &str) -> Result<T> {
+        match self.frame_inner() {
+            Some(frame_inner) => frame_inner.evaluate_as(expression).await,
+            None => Err(anyhow!("Frame inner is dropped")),
+        }
+    }
+
+    ///Sends a raw CDP command on the page session and returns the raw result.
+    pub async fn send_cdp(&self, method: &str, params: Value) -> Result<Value> {
+        match self.frame_inner() {
+            Some(frame_inner) => frame_inner.send_cdp(method, params).await,
+            None => Err(anyhow!("Frame inner is dropped")),
+        }
+    }
+
     pub async fn navigate(
         &self,
         url: &str,
@@ -71,6 +174,13 @@ impl Page {
         }
     }
 
+    pub async fn set_content(&self, html: &str, base_url: Option<&str>) -> Result<()> {
+        match self.frame_inner() {
+            Some(frame_inner) => frame_inner.set_content(html, base_url).await,
+            None => Err(anyhow!("Frame inner is dropped")),
+        }
+    }
+
     pub async fn reload(&self, wait_until: Option<&str>, timeout: Option<Duration>) -> Result<()> {
         match self.frame_inner() {
             Some(frame_inner) => frame_inner.reload(wait_until, timeout).await,
@@ -96,6 +206,31 @@ impl Page {
         }
     }
 
+    pub async fn close_and_wait(self, timeout: Option<Duration>) -> Result<()> {
+        match self.frame_inner() {
+            Some(frame_inner) => frame_inner.close_and_wait(timeout).await,
+            None => Err(anyhow!("Frame inner is dropped")),
+        }
+    }
+
+    pub async fn scroll_gesture(
+        &self,
+        x: f64,
+        y: f64,
+        x_distance: f64,
+        y_distance: f64,
+        speed: Option<i32>,
+    ) -> Result<()> {
+        match self.frame_inner() {
+            Some(frame_inner) => {
+                frame_inner
+                    .scroll_gesture(x, y, x_distance, y_distance, speed)
+                    .await
+            }
+            None => Err(anyhow!("Frame inner is dropped")),
+        }
+    }
+
     pub async fn set_credentials(&self, username: &str, password: &str) -> Result<()> {
         match self.frame_inner() {
             Some(frame_inner) => frame_inner.set_credentials(username, password).await,
@@ -103,6 +238,122 @@ impl Page {
         }
     }
 
+    pub async fn scroll_position(&self) -> Result<(f64, f64)> {
+        match self.frame_inner() {
+            Some(frame_inner) => frame_inner.scroll_position().await,
+            None => Err(anyhow!("Frame inner is dropped")),
+        }
+    }
+
+    pub async fn scroll_to(&self, x: f64, y: f64) -> Result<()> {
+        match self.frame_inner() {
+            Some(frame_inner) => frame_inner.scroll_to(x, y).await,
+            None => Err(anyhow!("Frame inner is dropped")),
+        }
+    }
+
+    pub async fn matches_media(&self, query: &str) -> Result<bool> {
+        match self.frame_inner() {
+            Some(frame_inner) => frame_inner.matches_media(query).await,
+            None => Err(anyhow!("Frame inner is dropped")),
+        }
+    }
+
+    pub async fn set_download_behavior(
+        &self,
+        behavior: DownloadBehavior,
+        path: Option<&str>,
+    ) -> Result<()> {
+        match self.frame_inner() {
+            Some(frame_inner) => frame_inner.set_download_behavior(behavior, path).await,
+            None => Err(anyhow!("Frame inner is dropped")),
+        }
+    }
+
+    pub async fn set_viewport(&self, device_metrics: DeviceMetrics) -> Result<()> {
+        match self.frame_inner() {
+            Some(frame_inner) => frame_inner.set_viewport(device_metrics).await,
+            None => Err(anyhow!("Frame inner is dropped")),
+        }
+    }
+
+    pub async fn emulate_media(&self, media: Option<&str>) -> Result<()> {
+        match self.frame_inner() {
+            Some(frame_inner) => frame_inner.emulate_media(media).await,
+            None => Err(anyhow!("Frame inner is dropped")),
+        }
+    }
+
+    pub async fn emulated_media(&self) -> Result<Option<String>> {
+        match self.frame_inner() {
+            Some(frame_inner) => Ok(frame_inner.emulated_media().await),
+            None => Err(anyhow!("Frame inner is dropped")),
+        }
+    }
+
+    pub async fn set_javascript_enabled(&self, enabled: bool) -> Result<()> {
+        match self.frame_inner() {
+            Some(frame_inner) => frame_inner.set_javascript_enabled(enabled).await,
+            None => Err(anyhow!("Frame inner is dropped")),
+        }
+    }
+
+    pub async fn javascript_enabled(&self) -> Result<bool> {
+        match self.frame_inner() {
+            Some(frame_inner) => Ok(frame_inner.javascript_enabled().await),
+            None => Err(anyhow!("Frame inner is dropped")),
+        }
+    }
+
+    pub async fn set_geolocation(&self, geolocation: Option<Geolocation>) -> Result<()> {
+        match self.frame_inner() {
+            Some(frame_inner) => frame_inner.set_geolocation(geolocation).await,
+            None => Err(anyhow!("Frame inner is dropped")),
+        }
+    }
+
+    pub async fn set_timezone(&self, timezone: Option<&str>) -> Result<()> {
+        match self.frame_inner() {
+            Some(frame_inner) => frame_inner.set_timezone(timezone).await,
+            None => Err(anyhow!("Frame inner is dropped")),
+        }
+    }
+
+    pub async fn set_locale(&self, locale: Option<&str>) -> Result<()> {
+        match self.frame_inner() {
+            Some(frame_inner) => frame_inner.set_locale(locale).await,
+            None => Err(anyhow!("Frame inner is dropped")),
+        }
+    }
+
+    pub async fn emulate_country(&self, country: Country) -> Result<()> {
+        match self.frame_inner() {
+            Some(frame_inner) => frame_inner.emulate_country(country).await,
+            None => Err(anyhow!("Frame inner is dropped")),
+        }
+    }
+
+    pub async fn with_media(&self, media: &str) -> Result<MediaEmulationGuard> {
+        match self.frame_inner() {
+            Some(frame_inner) => frame_inner.with_media(media).await,
+            None => Err(anyhow!("Frame inner is dropped")),
+        }
+    }
+
+    pub async fn viewport(&self) -> Result<Option<DeviceMetrics>> {
+        match self.frame_inner() {
+            Some(frame_inner) => Ok(frame_inner.viewport().await),
+            None => Err(anyhow!("Frame inner is dropped")),
+        }
+    }
+
+    pub async fn device_pixel_ratio(&self) -> Result<f64> {
+        match self.frame_inner() {
+            Some(frame_inner) => frame_inner.device_pixel_ratio().await,
+            None => Err(anyhow!("Frame inner is dropped")),
+        }
+    }
+
     pub async fn set_user_agent(&self, user_agent: UserAgentOverride) -> Result<()> {
         match self.frame_inner() {
             Some(frame_inner) => frame_inner.set_user_agent(user_agent).await,
@@ -124,12 +375,19 @@ impl Page {
         quality: Option<u64>,
         full_page: Option<bool>,
     ) -> Result<String> {
+        self.screenshot_with(ScreenshotOptions {
+            save_path,
+            format,
+            quality,
+            full_page,
+            hide_caret: false,
+        })
+        .await
+    }
+
+    pub async fn screenshot_with(&self, options: ScreenshotOptions<'_>) -> Result<String> {
         match self.frame_inner() {
-            Some(frame_inner) => {
-                frame_inner
-                    .screenshot(save_path, None, format, quality, full_page)
-                    .await
-            }
+            Some(frame_inner) => frame_inner.screenshot(None, options).await,
             None => Err(anyhow!("Frame inner is dropped")),
         }
     }
@@ -166,6 +424,33 @@ impl Page {
         }
     }
 
+    pub async fn expect_response<F, Fut, T>(
+        &self,
+        predicate: F,
+        action: Fut,
+        timeout: Option<Duration>,
+    ) -> Result<HttpResponse>
+    where
+        F: Fn(&HttpResponse) -> bool + Send + 'static,
+        Fut: Future<Output = Result<T>>,
+    {
+        match self.frame_inner() {
+            Some(frame_inner) => {
+                frame_inner
+                    .expect_response(predicate, action, timeout)
+                    .await
+            }
+            None => Err(anyhow!("Frame inner is dropped")),
+        }
+    }
+
+    pub async fn import_cookies(&self, jar: &str) -> Result<()> {
+        match self.frame_inner() {
+            Some(frame_inner) => frame_inner.import_cookies(jar).await,
+            None => Err(anyhow!("Frame inner is dropped")),
+        }
+    }
+
     pub async fn set_extra_headers(&self, headers: HashMap<&str, &str>) -> Result<()> {
         match self.frame_inner() {
             Some(frame_inner) => frame_inner.set_extra_headers(headers).await,
@@ -173,6 +458,20 @@ impl Page {
         }
     }
 
+    pub async fn add_extra_header(&self, name: &str, value: &str) -> Result<()> {
+        match self.frame_inner() {
+            Some(frame_inner) => frame_inner.add_extra_header(name, value).await,
+            None => Err(anyhow!("Frame inner is dropped")),
+        }
+    }
+
+    pub async fn remove_extra_header(&self, name: &str) -> Result<()> {
+        match self.frame_inner() {
+            Some(frame_inner) => frame_inner.remove_extra_header(name).await,
+            None => Err(anyhow!("Frame inner is dropped")),
+        }
+    }
+
     pub async fn clear_extra_headers(&self) -> Result<()> {
         match self.frame_inner() {
             Some(frame_inner) => frame_inner.clear_extra_headers().await,
@@ -180,6 +479,58 @@ impl Page {
         }
     }
 
+    pub async fn subscribe_to_failed_requests(&self) -> Result<broadcast::Receiver<FailedRequest>> {
+        match self.frame_inner() {
+            Some(frame_inner) => Ok(frame_inner.subscribe_to_failed_requests().await),
+            None => Err(anyhow!("Frame inner is dropped")),
+        }
+    }
+
+    pub async fn start_input_recording(&self) -> Result<()> {
+        match self.frame_inner() {
+            Some(frame_inner) => {
+                frame_inner.start_input_recording().await;
+                Ok(())
+            }
+            None => Err(anyhow!("Frame inner is dropped")),
+        }
+    }
+
+    pub async fn stop_input_recording(&self) -> Result<InputRecording> {
+        match self.frame_inner() {
+            Some(frame_inner) => Ok(frame_inner.stop_input_recording().await),
+            None => Err(anyhow!("Frame inner is dropped")),
+        }
+    }
+
+    pub async fn replay_input(&self, recording: &InputRecording) -> Result<()> {
+        match self.frame_inner() {
+            Some(frame_inner) => frame_inner.replay_input(recording).await,
+            None => Err(anyhow!("Frame inner is dropped")),
+        }
+    }
+
+    pub async fn pending_request_count(&self) -> Result<usize> {
+        match self.frame_inner() {
+            Some(frame_inner) => Ok(frame_inner.pending_request_count().await),
+            None => Err(anyhow!("Frame inner is dropped")),
+        }
+    }
+
+    pub async fn subscribe_to_console(&self) -> Result<ConsoleStream> {
+        match self.frame_inner() {
+            Some(frame_inner) => frame_inner.subscribe_to_console().await,
+            None => Err(anyhow!("Frame inner is dropped")),
+        }
+    }
+
+    pub async fn subscribe_to_page_errors(&self) -> Result<PageErrorStream> {
+        match self.frame_inner() {
+            Some(frame_inner) => frame_inner.subscribe_to_page_errors().await,
+            None => Err(anyhow!("Frame inner is dropped")),
+        }
+    }
+
     pub async fn subscribe_to_js_dialogs(&self) -> Result<broadcast::Receiver<JsDialog>> {
         match self.frame_inner() {
             Some(frame_inner) => frame_inner.subscribe_to_js_dialogs().await,
@@ -215,6 +566,16 @@ impl Page {
         }
     }
 
+    pub async fn query_selector_all_stream(
+        &self,
+        query: &str,
+    ) -> Result<BoxStream<'static, Result<Element>>> {
+        match self.frame_inner() {
+            Some(frame_inner) => frame_inner.query_selector_all_stream(query, None).await,
+            None => Err(anyhow!("Frame inner is dropped")),
+        }
+    }
+
     // pub async fn wait_for_selector(
     //     &self,
     //     query: &str,
@@ -242,6 +603,68 @@ impl Page {
         }
     }
 
+    pub async fn wait_for_any_selector(
+        &self,
+        queries: &[&str],
+        timeout: Option<Duration>,
+    ) -> Result<(usize, Element)> {
+        match self.frame_inner() {
+            Some(frame_inner) => frame_inner.wait_for_any_selector(queries, timeout).await,
+            None => Err(anyhow!("Frame inner is dropped")),
+        }
+    }
+
+    ///Elements are grouped by frame, each frame's attributes are read in one batch.
+    pub async fn get_attributes_for(
+        &self,
+        elements: &[Element],
+    ) -> Result<Vec<HashMap<String, String>>> {
+        let mut frames: Vec<(Arc<FrameInner>, Vec<usize>, Vec<BackendNodeId>)> = Vec::new();
+        for (index, element) in elements.iter().enumerate() {
+            let Some(frame_inner) = element.frame_inner() else {
+                return Err(anyhow!("Frame inner is not available"));
+            };
+            match frames
+                .iter_mut()
+                .find(|(frame, _, _)| Arc::ptr_eq(frame, &frame_inner))
+            {
+                Some((_, indexes, backend_node_ids)) => {
+                    indexes.push(index);
+                    backend_node_ids.push(element.backend_node_id());
+                }
+                None => frames.push((frame_inner, vec![index], vec![element.backend_node_id()])),
+            }
+        }
+
+        let mut result = vec![HashMap::new(); elements.len()];
+        for (frame_inner, indexes, backend_node_ids) in frames {
+            let attributes = frame_inner.get_attributes_for(&backend_node_ids).await?;
+            for (index, attributes) in indexes.into_iter().zip(attributes) {
+                result[index] = attributes;
+            }
+        }
+        Ok(result)
+    }
+
+    pub async fn wait_for_function(
+        &self,
+        function: &str,
+        args: Vec<Value>,
+        timeout: Option<Duration>,
+    ) -> Result<Option<Element>> {
+        match self.frame_inner() {
+            Some(frame_inner) => frame_inner.wait_for_function(function, args, timeout).await,
+            None => Err(anyhow!("Frame inner is dropped")),
+        }
+    }
+
+    pub async fn web_vitals(&self) -> Result<WebVitals> {
+        match self.frame_inner() {
+            Some(frame_inner) => frame_inner.web_vitals().await,
+            None => Err(anyhow!("Frame inner is dropped")),
+        }
+    }
+
     pub async fn add_evaluate_on_new_document(&self, script: &str) -> Result<ScriptIdentifier> {
         match self.frame_inner() {
             Some(frame_inner) => frame_inner.add_evaluate_on_new_document(script).await,
@@ -249,6 +672,28 @@ impl Page {
         }
     }
 
+    pub async fn add_evaluate_on_new_document_in_world(
+        &self,
+        script: &str,
+        world_name: &str,
+    ) -> Result<ScriptIdentifier> {
+        match self.frame_inner() {
+            Some(frame_inner) => {
+                frame_inner
+                    .add_evaluate_on_new_document_in_world(script, world_name)
+                    .await
+            }
+            None => Err(anyhow!("Frame inner is dropped")),
+        }
+    }
+
+    pub async fn evaluate_in_world(&self, world_name: &str, expression: &str) -> Result<Value> {
+        match self.frame_inner() {
+            Some(frame_inner) => frame_inner.evaluate_in_world(world_name, expression).await,
+            None => Err(anyhow!("Frame inner is dropped")),
+        }
+    }
+
     pub async fn remove_evaluate_on_new_document(
         &self,
         script_identifier: &ScriptIdentifier,
@@ -263,6 +708,55 @@ impl Page {
         }
     }
 
+    pub async fn add_virtual_authenticator(
+        &self,
+        options: VirtualAuthenticatorOptions,
+    ) -> Result<AuthenticatorId> {
+        match self.frame_inner() {
+            Some(frame_inner) => frame_inner.add_virtual_authenticator(&options).await,
+            None => Err(anyhow!("Frame inner is dropped")),
+        }
+    }
+
+    pub async fn remove_virtual_authenticator(
+        &self,
+        authenticator_id: &AuthenticatorId,
+    ) -> Result<()> {
+        match self.frame_inner() {
+            Some(frame_inner) => {
+                frame_inner
+                    .remove_virtual_authenticator(authenticator_id)
+                    .await
+            }
+            None => Err(anyhow!("Frame inner is dropped")),
+        }
+    }
+
+    pub async fn add_credential(
+        &self,
+        authenticator_id: &AuthenticatorId,
+        credential: Credential,
+    ) -> Result<()> {
+        match self.frame_inner() {
+            Some(frame_inner) => {
+                frame_inner
+                    .add_credential(authenticator_id, &credential)
+                    .await
+            }
+            None => Err(anyhow!("Frame inner is dropped")),
+        }
+    }
+
+    pub async fn get_credentials(
+        &self,
+        authenticator_id: &AuthenticatorId,
+    ) -> Result<Vec<Credential>> {
+        match self.frame_inner() {
+            Some(frame_inner) => frame_inner.get_credentials(authenticator_id).await,
+            None => Err(anyhow!("Frame inner is dropped")),
+        }
+    }
+
     // pub async fn wait_for_dom_storage_item_added(
     //     &self,
     //     key: &str,
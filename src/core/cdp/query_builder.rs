@@ -29,6 +29,12 @@ enum ExecuteSelectorResult {
     Multiple(Vec<(BackendNodeId, Arc<FrameInner>)>),
 }
 
+///Single CSS selector the browser can match natively, without text finders, shadow piercing or alternatives.
+///Any `>` counts as not plain, the step parser treats even a child combinator on its own.
+pub(crate) fn is_plain_css(query: &str) -> bool {
+    !query.starts_with("text(") && !query.contains('>') && !query.contains(',')
+}
+
 #[derive(Debug, Clone)]
 pub struct QueryBuilder<'a> {
     query: &'a str,
@@ -85,11 +91,10 @@ impl<'a> QueryBuilder<'a> {
                 let start_backend_node_id = if let Some(backend_node_id) = &self.backend_node_id {
                     *backend_node_id
                 } else {
-                    let node = match frame_inner.node(0).await {
-                        Ok(node) => node,
+                    match frame_inner.root_backend_node_id().await {
+                        Ok(backend_node_id) => backend_node_id,
                         Err(_) => return Ok(None),
-                    };
-                    node.backend_node_id
+                    }
                 };
 
                 if let Some(result) = self
@@ -139,11 +144,10 @@ impl<'a> QueryBuilder<'a> {
                 let start_backend_node_id = if let Some(backend_node_id) = &self.backend_node_id {
                     *backend_node_id
                 } else {
-                    let node = match frame_inner.node(0).await {
-                        Ok(node) => node,
+                    match frame_inner.root_backend_node_id().await {
+                        Ok(backend_node_id) => backend_node_id,
                         Err(_) => return Ok(None),
-                    };
-                    node.backend_node_id
+                    }
                 };
 
                 if let Some(result) = self
@@ -378,11 +382,11 @@ impl<'a> QueryBuilder<'a> {
                 Some(frame_inner) => frame_inner,
                 None => return None,
             };
-            let node = match frame_inner.node(0).await {
-                Ok(node) => node,
+            let backend_node_id = match frame_inner.root_backend_node_id().await {
+                Ok(backend_node_id) => backend_node_id,
                 Err(_) => return None,
             };
-            return Some((node.backend_node_id, frame_inner));
+            return Some((backend_node_id, frame_inner));
         }
         Some((node.backend_node_id, frame_inner))
     }
@@ -435,11 +439,11 @@ impl<'a> QueryBuilder<'a> {
                     Some(frame_inner) => frame_inner,
                     None => continue,
                 };
-                let node = match frame_inner.node(0).await {
-                    Ok(node) => node,
+                let backend_node_id = match frame_inner.root_backend_node_id().await {
+                    Ok(backend_node_id) => backend_node_id,
                     Err(_) => continue,
                 };
-                backend_node_ids.push((node.backend_node_id, frame_inner));
+                backend_node_ids.push((backend_node_id, frame_inner));
             } else {
                 backend_node_ids.push((node.backend_node_id, frame_inner.clone()));
             }
@@ -612,3 +616,76 @@ impl<'a> QueryBuilder<'a> {
         ))))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::super::mock::{MockBrowser, default_reply};
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn combinators_and_alternatives_are_not_plain_css() {
+        assert!(is_plain_css("tr.row td"));
+        assert!(!is_plain_css("ul > li"));
+        assert!(!is_plain_css("host >>> button"));
+        assert!(!is_plain_css("a, button"));
+        assert!(!is_plain_css("text(Submit)"));
+    }
+
+    #[test]
+    fn splits_selector_into_shadow_steps() {
+        let builder = QueryBuilder::new("", Weak::new(), None);
+        let path = builder
+            .parse_selector_path("my-app >>> div.item > span >>>> button")
+            .unwrap()
+            .unwrap();
+        let steps: Vec<String> = path
+            .steps
+            .iter()
+            .map(|step| match step {
+                SelectorStep::ShadowRootDeep => ">>>".to_string(),
+                SelectorStep::ShadowRootDirect => ">>>>".to_string(),
+                SelectorStep::CssSelector(selector) => selector.clone(),
+            })
+            .collect();
+        assert_eq!(
+            steps,
+            vec!["my-app", ">>>", "div.item", "span", ">>>>", "button"]
+        );
+    }
+
+    #[tokio::test]
+    async fn resolves_css_selector_to_backend_node() {
+        let browser = MockBrowser::start(|method, params, session_id| match method {
+            "DOM.getDocument" => Ok(json!({ "root": { "backendNodeId": 1, "nodeType": 9 } })),
+            "DOM.pushNodesByBackendIdsToFrontend" => Ok(json!({ "nodeIds": [1] })),
+            "DOM.querySelector" if params["selector"] == "#missing" => Ok(json!({ "nodeId": 0 })),
+            "DOM.querySelector" => Ok(json!({ "nodeId": 7 })),
+            "DOM.describeNode" if params["nodeId"] == 7 => {
+                Ok(json!({ "node": { "backendNodeId": 42, "nodeType": 1 } }))
+            }
+            "DOM.describeNode" => Err("Could not find node with given id".to_string()),
+            _ => default_reply(method, params, session_id),
+        })
+        .await;
+        let (_connection, _, frame_inner) = browser.page_frame().await;
+
+        let found = QueryBuilder::new("#submit", Arc::downgrade(&frame_inner), None)
+            .parse()
+            .await
+            .unwrap();
+        let (backend_node_id, found_frame) = found.unwrap();
+        assert_eq!(backend_node_id, 42);
+        assert!(Arc::ptr_eq(&found_frame, &frame_inner));
+
+        let query = browser.commands("DOM.querySelector");
+        assert_eq!(query[0].params["nodeId"], 1);
+        assert_eq!(query[0].params["selector"], "#submit");
+
+        let missing = QueryBuilder::new("#missing", Arc::downgrade(&frame_inner), None)
+            .parse()
+            .await
+            .unwrap();
+        assert!(missing.is_none());
+    }
+}
@@ -5,9 +5,9 @@ use std::sync::{
     Arc, Weak,
     atomic::{AtomicI32, Ordering},
 };
-use tokio::sync::{Mutex, mpsc};
+use tokio::sync::Mutex;
 
-use super::connection::{Connection, Event, Response};
+use super::connection::{Connection, EventReceiver, Response};
 use super::domains::browser::BrowserContextID;
 use super::domains::dom::*;
 use super::domains::fetch::*;
@@ -16,10 +16,29 @@ use super::domains::page::*;
 use super::domains::runtime::*;
 use super::domains::target::*;
 use super::emulation_manager::EmulationManager;
+use super::input_recorder::InputRecorder;
 use super::js_manager::JsManager;
 use super::network_manager::NetworkManager;
 use super::target_manager::TargetManager;
 
+#[derive(Debug)]
+pub struct RuntimeGuard {
+    target: Weak<Target>,
+}
+
+impl Drop for RuntimeGuard {
+    fn drop(&mut self) {
+        let target = self.target.clone();
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            handle.spawn(async move {
+                if let Some(target) = target.upgrade() {
+                    target.disable_runtime().await;
+                }
+            });
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Target {
     connection: Weak<Connection>,
@@ -33,6 +52,7 @@ pub struct Target {
     emulation_manager: Option<Arc<EmulationManager>>,
     js_manager: Option<Arc<JsManager>>,
     dom_lock: Arc<Mutex<()>>,
+    input_recorder: Arc<InputRecorder>,
 }
 
 impl Target {
@@ -56,6 +76,7 @@ impl Target {
             emulation_manager: None,
             js_manager: None,
             dom_lock: Arc::new(Mutex::new(())),
+            input_recorder: Arc::new(InputRecorder::default()),
         }
     }
 
@@ -101,15 +122,19 @@ impl Target {
         };
 
         match conn.send(method, params, Some(&self.session_id())).await {
-            Ok(response) => Ok(response),
+            Ok(response) => {
+                self.input_recorder.record(method, params);
+                Ok(response)
+            }
             Err(e) => Err(e),
         }
     }
 
-    pub async fn subscribe(
-        &self,
-        methods: DashSet<String>,
-    ) -> Result<mpsc::UnboundedReceiver<Arc<Event>>> {
+    pub fn input_recorder(&self) -> Arc<InputRecorder> {
+        self.input_recorder.clone()
+    }
+
+    pub async fn subscribe(&self, methods: DashSet<String>) -> Result<EventReceiver> {
         let Some(conn) = self.connection() else {
             return Err(anyhow!("Connection is not available"));
         };
@@ -121,6 +146,19 @@ impl Target {
         Ok(rx)
     }
 
+    ///Like `subscribe`, but buffered by the connection's `EventChannelMode`, for streams given to users.
+    pub async fn subscribe_stream(&self, methods: DashSet<String>) -> Result<EventReceiver> {
+        let Some(conn) = self.connection() else {
+            return Err(anyhow!("Connection is not available"));
+        };
+
+        let session_ids = DashSet::with_capacity(1);
+        session_ids.insert(self.session_id());
+
+        let (_, rx) = conn.subscribe_stream(methods, session_ids).await;
+        Ok(rx)
+    }
+
     //Weaked parent to prevent some bugs with target (UB of CDP);
     pub async fn init(&mut self, parent_target: Option<Weak<Target>>) -> Result<()> {
         // match self
@@ -253,7 +291,9 @@ impl Target {
                 Some(connection) => connection,
                 None => return Err(anyhow!("Connection is not available")),
             };
-            let network_manager = NetworkManager::new(Arc::downgrade(&connection));
+            //Top level target id is the id of its main frame
+            let network_manager =
+                NetworkManager::new(Arc::downgrade(&connection), self.target_id());
             let emulation_manager = EmulationManager::new(Arc::downgrade(&connection));
             let js_manager = JsManager::new(Arc::downgrade(&connection));
 
@@ -316,21 +356,32 @@ impl Target {
     }
 
     pub async fn disable_runtime(&self) {
-        let old_count = self.runtime_ref_count.load(Ordering::SeqCst);
-
-        if old_count == 0 {
-            return;
-        }
-
-        let old_count = self.runtime_ref_count.fetch_sub(1, Ordering::SeqCst);
+        //Load and decrement in one step, otherwise two concurrent calls could push the count below zero.
+        let old_count =
+            match self
+                .runtime_ref_count
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |count| {
+                    if count > 0 { Some(count - 1) } else { None }
+                }) {
+                Ok(old_count) => old_count,
+                Err(_) => return,
+            };
 
-        if old_count > 0 && old_count == 1 {
+        if old_count == 1 {
             let _ = self
                 .send("Runtime.disable", &RuntimeDisable::default())
                 .await;
         }
     }
 
+    ///Keeps Runtime enabled for as long as the guard lives.
+    pub async fn runtime_guard(self: &Arc<Self>) -> RuntimeGuard {
+        self.enable_runtime().await;
+        RuntimeGuard {
+            target: Arc::downgrade(self),
+        }
+    }
+
     pub async fn shutdown(self) -> Result<()> {
         let (network_manager, emulation_manager, js_manager) = (
             self.network_manager(),
@@ -383,3 +434,36 @@ impl Target {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::super::mock::{MockBrowser, default_reply, eventually};
+
+    #[tokio::test]
+    async fn runtime_stays_enabled_until_last_guard_is_dropped() {
+        let browser = MockBrowser::start(default_reply).await;
+        let (_connection, target_manager, _) = browser.page_frame().await;
+        let target = target_manager
+            .get_target(&"PAGE".to_string())
+            .await
+            .unwrap();
+
+        let first = target.runtime_guard().await;
+        let second = target.runtime_guard().await;
+        assert_eq!(browser.commands("Runtime.enable").len(), 1);
+
+        drop(first);
+        tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+        assert!(browser.commands("Runtime.disable").is_empty());
+
+        drop(second);
+        eventually(|| async { !browser.commands("Runtime.disable").is_empty() }).await;
+
+        //Extra disable must not push the count below zero
+        target.disable_runtime().await;
+        assert_eq!(browser.commands("Runtime.disable").len(), 1);
+
+        let _third = target.runtime_guard().await;
+        assert_eq!(browser.commands("Runtime.enable").len(), 2);
+    }
+}
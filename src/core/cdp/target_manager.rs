@@ -1,8 +1,9 @@
 use anyhow::{Result, anyhow};
 use dashmap::{DashMap, DashSet};
+use futures::future::join_all;
 use std::collections::HashMap;
 use std::sync::{Arc, Weak};
-use tokio::sync::{RwLock, mpsc, oneshot};
+use tokio::sync::{RwLock, broadcast, oneshot};
 use tokio::task::JoinHandle;
 
 use super::browser_context::BrowserContext;
@@ -14,6 +15,20 @@ use super::domains::target::*;
 use super::frame_inner::FrameInner;
 use super::page::Page;
 use super::target::Target;
+use super::worker::Worker;
+
+///Target lifecycle for every target type, not just pages: workers, service workers and so on.
+#[derive(Debug, Clone)]
+pub enum TargetEvent {
+    ///Emitted after page targets are attached, so `get_page` already finds them.
+    Created(TargetInfo),
+    Destroyed(TargetId),
+    Crashed {
+        target_id: TargetId,
+        status: String,
+        error_code: String,
+    },
+}
 
 //Here's so much Arc for TargetId and FrameId, but i gained 30-40% of performance.
 //Previously, i cloned them so much espessially for quering elements (but cloning strings costs so much), so it was good idea, i guess.
@@ -27,9 +42,10 @@ pub struct TargetManager {
     pending_targets: Arc<RwLock<HashMap<Arc<TargetId>, oneshot::Sender<Weak<Target>>>>>,
     // First TargetId is Parent to await for, Second is Child-iframe, which waits for parent to be created; We pass Target to be able to take it as mut and init it;
     pending_iframes: DashMap<(Arc<TargetId>, Arc<TargetId>), Target>,
-    iframe_channel: Arc<RwLock<Option<mpsc::Sender<Arc<TargetId>>>>>,
     target_event_handler: Arc<RwLock<Option<JoinHandle<()>>>>,
-    iframe_channel_handler: Arc<RwLock<Option<JoinHandle<()>>>>,
+    target_event_sender: broadcast::Sender<TargetEvent>,
+    workers: DashMap<Arc<TargetId>, Arc<Worker>>,
+    created_pages: DashSet<TargetId>,
 }
 
 impl TargetManager {
@@ -41,9 +57,10 @@ impl TargetManager {
             frame_inners: DashMap::with_capacity(1024),
             pending_targets: Arc::new(RwLock::new(HashMap::with_capacity(1024))),
             pending_iframes: DashMap::with_capacity(1024),
-            iframe_channel: Arc::new(RwLock::new(None)),
             target_event_handler: Arc::new(RwLock::new(None)),
-            iframe_channel_handler: Arc::new(RwLock::new(None)),
+            target_event_sender: broadcast::channel(1024).0,
+            workers: DashMap::new(),
+            created_pages: DashSet::new(),
         }
     }
 
@@ -100,12 +117,13 @@ impl TargetManager {
             return Err(anyhow!("Connection is not available"));
         };
 
-        let methods = DashSet::with_capacity(5);
+        let methods = DashSet::with_capacity(6);
         methods.insert("Target.targetCreated".to_string());
         methods.insert("Target.targetDestroyed".to_string());
         methods.insert("Target.targetCrashed".to_string());
         methods.insert("Page.frameAttached".to_string());
         methods.insert("Page.frameDetached".to_string());
+        methods.insert("Page.frameNavigated".to_string());
 
         let session_ids = DashSet::with_capacity(1024);
 
@@ -119,7 +137,6 @@ impl TargetManager {
         .await?;
 
         let downgraded_manager = Arc::downgrade(&self);
-        let downgraded_manager_clone = downgraded_manager.clone();
 
         let event_handler = tokio::spawn(async move {
             while let Some(event) = rx.recv().await {
@@ -139,6 +156,9 @@ impl TargetManager {
                             // Err(e) => eprintln!("Error on_target_created: {}", e),
                             Err(_) => (),
                         }
+                        let _ = manager
+                            .target_event_sender
+                            .send(TargetEvent::Created(created.target_info.clone()));
                     }
                     EventParams::TargetDestroyed(destroyed) => {
                         match manager.on_target_destroyed(destroyed).await {
@@ -146,6 +166,9 @@ impl TargetManager {
                             // Err(e) => eprintln!("Error on_target_destroyed: {}", e),
                             Err(_) => (),
                         }
+                        let _ = manager
+                            .target_event_sender
+                            .send(TargetEvent::Destroyed(destroyed.target_id.clone()));
                     }
                     EventParams::TargetCrashed(crashed) => {
                         match manager.on_target_crashed(crashed).await {
@@ -153,6 +176,11 @@ impl TargetManager {
                             // Err(e) => eprintln!("Error on_target_crashed: {}", e),
                             Err(_) => (),
                         }
+                        let _ = manager.target_event_sender.send(TargetEvent::Crashed {
+                            target_id: crashed.target_id.clone(),
+                            status: crashed.status.clone(),
+                            error_code: crashed.error_code.clone(),
+                        });
                     }
                     EventParams::FrameAttached(attached) => {
                         let session_id = event.session_id.as_ref().unwrap();
@@ -169,6 +197,9 @@ impl TargetManager {
                             Err(_) => (),
                         }
                     }
+                    EventParams::FrameNavigated(navigated) => {
+                        manager.on_frame_navigated(navigated).await;
+                    }
                     _ => {
                         println!("Unknown event: {:?}", event);
                     }
@@ -181,69 +212,6 @@ impl TargetManager {
         let mut target_event_handler = self.target_event_handler.write().await;
         *target_event_handler = Some(event_handler);
 
-        // We send parent target id to iframe channel to be handled later
-        let (iframe_tx, mut iframe_rx) = mpsc::channel::<Arc<TargetId>>(1024);
-
-        *self.iframe_channel.write().await = Some(iframe_tx);
-
-        // let pending_iframes = self.pending_iframes.clone();
-        // let targets = self.targets.clone();
-
-        let channel_handler = tokio::spawn(async move {
-            while let Some(parent_id) = iframe_rx.recv().await {
-                let manager = match downgraded_manager_clone.upgrade() {
-                    Some(manager) => manager,
-                    None => break,
-                };
-
-                let mut keys_to_remove = Vec::with_capacity(manager.pending_iframes.len());
-                manager
-                    .pending_iframes
-                    .iter()
-                    .filter(|entry| entry.key().0 == parent_id)
-                    .for_each(|entry| {
-                        keys_to_remove.push(entry.key().clone());
-                    });
-                keys_to_remove.shrink_to_fit();
-
-                for key in keys_to_remove {
-                    if let Some((_, mut target)) = manager.pending_iframes.remove(&key) {
-                        let parent_target = manager.targets.get(&key.0).unwrap().clone();
-                        let _ = target.init(Some(Arc::downgrade(&parent_target))).await;
-                        let arc_target = Arc::new(target);
-
-                        let frame_inner = manager.get_frame_inner(&key.1).await;
-                        match frame_inner {
-                            Some(frame_inner) => {
-                                frame_inner
-                                    .init_as_target(Arc::downgrade(&arc_target))
-                                    .await;
-                            }
-                            None => {
-                                let frame_inner = FrameInner::new(
-                                    Arc::downgrade(&arc_target),
-                                    key.1.clone(),
-                                    Some(key.0.clone()),
-                                    None,
-                                );
-                                //Actually, if we are in this block, it means that parent frame exists, so we can unwrap.
-                                let parent_frame_inner =
-                                    manager.get_frame_inner(&key.0).await.unwrap();
-                                parent_frame_inner.add_child_frame(key.1.clone()).await;
-
-                                manager.add_frame_inner(Arc::new(frame_inner)).await;
-                            }
-                        }
-
-                        let _ = manager.targets.insert(key.1.clone(), arc_target);
-                    }
-                }
-            }
-        });
-
-        let mut iframe_channel_handler = self.iframe_channel_handler.write().await;
-        *iframe_channel_handler = Some(channel_handler);
-
         Ok(())
     }
 
@@ -252,10 +220,6 @@ impl TargetManager {
         if let Some(handler) = target_event_handler.as_ref() {
             handler.abort();
         }
-        let iframe_channel_handler = self.iframe_channel_handler.read().await;
-        if let Some(handler) = iframe_channel_handler.as_ref() {
-            handler.abort();
-        }
     }
 
     pub async fn on_frame_attached(
@@ -330,6 +294,9 @@ impl TargetManager {
         let target_type = target_info.target_type;
         match target_type.as_str() {
             "iframe" | "page" | "webview" | "tab" => {}
+            "worker" | "shared_worker" | "service_worker" => {
+                return self.on_worker_created(params.target_info.clone()).await;
+            }
             _ => return Ok(()),
         };
 
@@ -404,12 +371,10 @@ impl TargetManager {
         );
 
         // If CDP with it's undefined behavior creates sometime child target before parent, we need to await for parent target to be created
-        // So, we send iframe target id to iframe channel to be handled later
+        // So, we park iframe target in pending_iframes until its parent is registered
         if target_type == "iframe" {
             if let Some(parent) = self.get_target(&parent_id.as_ref().unwrap()).await {
                 self.add_target(target, Some(Arc::downgrade(&parent))).await;
-                //iframe might be parent for another iframe as well
-                let _ = self.send_to_iframe_channel(target_id.clone()).await;
 
                 let target = self.get_target(&target_id).await.unwrap();
                 let target_id = target.target_id();
@@ -432,10 +397,13 @@ impl TargetManager {
                 let parent_frame_inner = self.get_frame_inner(&parent_id.as_ref().unwrap()).await;
                 match parent_frame_inner {
                     Some(parent_frame_inner) => {
-                        parent_frame_inner.add_child_frame(target_id).await;
+                        parent_frame_inner.add_child_frame(target_id.clone()).await;
                     }
                     None => {}
                 }
+
+                //iframe might be parent for another iframe as well
+                self.attach_pending_iframes(target_id).await;
             } else {
                 self.pending_iframes.insert(
                     (parent_id.as_ref().unwrap().clone(), target_id.clone()),
@@ -444,13 +412,14 @@ impl TargetManager {
             }
         } else {
             self.add_target(target, None).await;
-            let _ = self.send_to_iframe_channel(target_id.clone()).await;
 
             let target = self.get_target(&target_id).await.unwrap();
             let target_id = target.target_id();
             let frame = FrameInner::new(Arc::downgrade(&target), target_id.clone(), None, None);
             self.add_frame_inner(Arc::new(frame)).await;
 
+            self.attach_pending_iframes(target_id.clone()).await;
+
             let mut pending_targets = self.pending_targets.write().await;
             if let Some(sender) = pending_targets.remove(&target_id) {
                 let _ = sender.send(Arc::downgrade(&target));
@@ -460,13 +429,97 @@ impl TargetManager {
         Ok(())
     }
 
-    async fn send_to_iframe_channel(&self, target_id: Arc<TargetId>) -> Result<()> {
-        if let Some(tx) = self.iframe_channel.read().await.as_ref() {
-            let _ = tx.send(target_id).await;
-        }
+    //Workers have neither Page domain nor frames, so they skip Target and its managers entirely.
+    async fn on_worker_created(&self, target_info: TargetInfo) -> Result<()> {
+        let conn = match self.connection() {
+            Some(conn) => conn,
+            None => return Err(anyhow!("Connection is not available")),
+        };
+        let target_id = Arc::new(target_info.target_id);
+        let session_id = conn
+            .send(
+                "Target.attachToTarget",
+                &AttachToTarget::default(&target_id),
+                None,
+            )
+            .await?
+            .result_as::<AttachToTargetResponse>()?
+            .session_id;
+        let worker = Worker::new(
+            Arc::downgrade(&conn),
+            target_id.clone(),
+            Arc::new(session_id),
+            target_info.target_type,
+            target_info.url,
+        );
+        self.workers.insert(target_id, Arc::new(worker));
         Ok(())
     }
 
+    pub async fn get_workers(&self) -> Vec<Arc<Worker>> {
+        self.workers
+            .iter()
+            .map(|entry| entry.value().clone())
+            .collect()
+    }
+
+    //Children are attached synchronously after the parent is registered, so their parent frame always exists.
+    async fn attach_pending_iframes(&self, parent_id: Arc<TargetId>) {
+        let mut parents = vec![parent_id];
+
+        while let Some(parent_id) = parents.pop() {
+            let keys: Vec<_> = self
+                .pending_iframes
+                .iter()
+                .filter(|entry| entry.key().0 == parent_id)
+                .map(|entry| entry.key().clone())
+                .collect();
+
+            for key in keys {
+                let Some((_, mut target)) = self.pending_iframes.remove(&key) else {
+                    continue;
+                };
+                let Some(parent_target) = self.get_target(&key.0).await else {
+                    continue;
+                };
+                let _ = target.init(Some(Arc::downgrade(&parent_target))).await;
+                let arc_target = Arc::new(target);
+
+                let frame_inner = self.get_frame_inner(&key.1).await;
+                match frame_inner {
+                    Some(frame_inner) => {
+                        frame_inner
+                            .init_as_target(Arc::downgrade(&arc_target))
+                            .await;
+                    }
+                    None => {
+                        let frame_inner = FrameInner::new(
+                            Arc::downgrade(&arc_target),
+                            key.1.clone(),
+                            Some(key.0.clone()),
+                            None,
+                        );
+                        self.add_frame_inner(Arc::new(frame_inner)).await;
+                    }
+                }
+
+                if let Some(parent_frame_inner) = self.get_frame_inner(&key.0).await {
+                    parent_frame_inner.add_child_frame(key.1.clone()).await;
+                }
+
+                self.targets.insert(key.1.clone(), arc_target);
+                //Nested iframes could wait for this one
+                parents.push(key.1.clone());
+            }
+        }
+    }
+
+    pub async fn on_frame_navigated(&self, params: &FrameNavigated) {
+        if let Some(frame_inner) = self.get_frame_inner(&params.frame.id).await {
+            frame_inner.on_navigated().await;
+        }
+    }
+
     pub async fn on_frame_detached(&self, params: &FrameDetached) -> Result<()> {
         let frame_id = Arc::new(params.frame_id.clone());
         let target = self.get_target(&frame_id).await;
@@ -497,6 +550,8 @@ impl TargetManager {
 
     pub async fn on_target_destroyed(&self, params: &TargetDestroyed) -> Result<()> {
         let target_id = Arc::new(params.target_id.clone());
+        self.workers.remove(&target_id);
+        self.created_pages.remove(target_id.as_ref());
         if let Some((_, target)) = self.targets.remove(&target_id) {
             let frame_inner = self.get_frame_inner(&target_id).await;
             match frame_inner {
@@ -523,6 +578,7 @@ impl TargetManager {
 
     pub async fn on_target_crashed(&self, params: &TargetCrashed) -> Result<()> {
         let target_id = Arc::new(params.target_id.clone());
+        self.workers.remove(&target_id);
         if let Some((_, target)) = self.targets.remove(&target_id) {
             let frame_inner = self.get_frame_inner(&target_id).await;
             match frame_inner {
@@ -600,6 +656,20 @@ impl TargetManager {
         Ok((target, target_id))
     }
 
+    pub fn subscribe_to_targets(&self) -> broadcast::Receiver<TargetEvent> {
+        self.target_event_sender.subscribe()
+    }
+
+    ///Page for a top level target, e.g. a tab opened by the site itself.
+    pub async fn get_page(&self, target_id: &TargetId) -> Option<Page> {
+        let target = self.get_target(target_id).await?;
+        if target.parent_id().is_some() {
+            return None;
+        }
+        let frame_inner = self.get_frame_inner(target_id).await?;
+        Some(Page::new(frame_inner))
+    }
+
     pub async fn create_page(&self, browser_context_id: Option<&BrowserContextID>) -> Result<Page> {
         let (_, target_id) = match self.create_target(browser_context_id).await {
             Ok((target, target_id)) => (target, target_id),
@@ -615,15 +685,61 @@ impl TargetManager {
                 return Err(anyhow!("Frame inner not found: {:?}", target_id));
             }
         };
+        self.created_pages.insert(target_id.to_string());
         let page = Page::new(frame_inner);
         Ok(page)
     }
 
+    ///Closes pages opened with `create_page` which are still alive.
+    pub async fn close_created_pages(&self) {
+        let Some(conn) = self.connection() else {
+            return;
+        };
+        let target_ids: Vec<TargetId> = self
+            .created_pages
+            .iter()
+            .map(|target_id| target_id.clone())
+            .collect();
+        let params: Vec<CloseTarget> = target_ids.iter().map(CloseTarget::default).collect();
+        let futures = params
+            .iter()
+            .map(|params| conn.send("Target.closeTarget", params, None));
+        join_all(futures).await;
+        self.created_pages.clear();
+    }
+
     pub async fn create_browser_context(
         &self,
         proxy: Option<&str>,
         proxy_bypass_list: Option<&str>,
     ) -> Result<Arc<BrowserContext>> {
+        let (browser_context_id, conn) = self
+            .send_create_browser_context(proxy, proxy_bypass_list)
+            .await?;
+        let browser_context = Arc::new(BrowserContext::new(
+            browser_context_id.clone(),
+            Arc::downgrade(&conn),
+        ));
+
+        self.browser_contexts
+            .insert(browser_context_id.clone(), browser_context.clone());
+        Ok(browser_context)
+    }
+
+    ///Not kept by the target manager, so dropping the last handle to it and its pages disposes it.
+    pub async fn create_incognito_context(&self) -> Result<Arc<BrowserContext>> {
+        let (browser_context_id, conn) = self.send_create_browser_context(None, None).await?;
+        Ok(Arc::new(BrowserContext::new_incognito(
+            browser_context_id,
+            Arc::downgrade(&conn),
+        )))
+    }
+
+    async fn send_create_browser_context(
+        &self,
+        proxy: Option<&str>,
+        proxy_bypass_list: Option<&str>,
+    ) -> Result<(BrowserContextID, Arc<Connection>)> {
         let conn = match self.connection() {
             Some(conn) => conn,
             None => return Err(anyhow!("Connection is not available")),
@@ -656,22 +772,33 @@ impl TargetManager {
                 Err(e) => return Err(e),
             };
 
-        let browser_context = Arc::new(BrowserContext::new(
-            browser_context_id.clone(),
-            Arc::downgrade(&conn),
-        ));
-
-        self.browser_contexts
-            .insert(browser_context_id.clone(), browser_context.clone());
-        Ok(browser_context)
+        Ok((browser_context_id, conn))
     }
 
+    ///Closes page targets of the context first, so none of them outlives it as an orphan.
     pub async fn close_browser_context(&self, browser_context_id: &BrowserContextID) -> Result<()> {
         let conn = match self.connection() {
             Some(conn) => conn,
             None => return Err(anyhow!("Connection is not available")),
         };
 
+        let target_ids: Vec<Arc<TargetId>> = self
+            .targets
+            .iter()
+            .filter(|target| {
+                target.target_type() == "page" && target.browser_context_id() == browser_context_id
+            })
+            .map(|target| target.target_id())
+            .collect();
+        let params: Vec<CloseTarget> = target_ids
+            .iter()
+            .map(|target_id| CloseTarget::default(target_id))
+            .collect();
+        let futures = params
+            .iter()
+            .map(|params| conn.send("Target.closeTarget", params, None));
+        join_all(futures).await;
+
         let _ = match conn
             .send(
                 "Target.disposeBrowserContext",
@@ -689,3 +816,365 @@ impl TargetManager {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::super::browser::Browser;
+    use super::super::mock::{MockBrowser, default_reply, eventually, frame_tree, session_for};
+    use super::TargetEvent;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn attaches_page_targets_and_registers_frames() {
+        let browser = MockBrowser::start(default_reply).await;
+        let (_connection, target_manager) = browser.connect_with_targets().await;
+
+        browser.emit_target_created("PAGE", "page");
+        browser.emit_target_created("BROWSER", "browser");
+
+        eventually(|| async {
+            target_manager
+                .get_frame_inner(&"PAGE".to_string())
+                .await
+                .is_some()
+        })
+        .await;
+
+        let target = target_manager
+            .get_target(&"PAGE".to_string())
+            .await
+            .unwrap();
+        assert_eq!(*target.session_id(), session_for("PAGE"));
+        assert_eq!(target.browser_context_id(), "default");
+        assert!(target.network_manager().is_some());
+        assert_eq!(target_manager.get_targets().await.len(), 1);
+
+        let attached = browser.commands("Target.attachToTarget");
+        assert_eq!(attached.len(), 1);
+        assert_eq!(attached[0].params["targetId"], "PAGE");
+        assert_eq!(browser.commands("Fetch.enable").len(), 1);
+
+        browser.emit(
+            "Target.targetDestroyed",
+            json!({ "targetId": "PAGE" }),
+            None,
+        );
+        eventually(|| async {
+            target_manager
+                .get_target(&"PAGE".to_string())
+                .await
+                .is_none()
+        })
+        .await;
+        assert!(
+            target_manager
+                .get_frame_inner(&"PAGE".to_string())
+                .await
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn iframe_target_shares_parent_managers() {
+        let browser = MockBrowser::start(|method, params, session_id| match (method, session_id) {
+            ("Page.getFrameTree", Some(session_id)) if session_id == session_for("CHILD") => {
+                Ok(frame_tree("CHILD", Some("PAGE")))
+            }
+            _ => default_reply(method, params, session_id),
+        })
+        .await;
+        let (_connection, target_manager, _) = browser.page_frame().await;
+        browser.emit_target_created("CHILD", "iframe");
+        eventually(|| async {
+            target_manager
+                .get_frame_inner(&"CHILD".to_string())
+                .await
+                .is_some()
+        })
+        .await;
+
+        let page = target_manager
+            .get_target(&"PAGE".to_string())
+            .await
+            .unwrap();
+        let child = target_manager
+            .get_target(&"CHILD".to_string())
+            .await
+            .unwrap();
+        assert_eq!(
+            child.parent_id().as_deref().map(|id| id.as_str()),
+            Some("PAGE")
+        );
+        assert!(std::sync::Arc::ptr_eq(
+            &page.network_manager().unwrap(),
+            &child.network_manager().unwrap()
+        ));
+
+        let page_frame = target_manager
+            .get_frame_inner(&"PAGE".to_string())
+            .await
+            .unwrap();
+        let child_frames = page_frame.child_frames().await.unwrap();
+        assert_eq!(child_frames.len(), 1);
+        assert_eq!(*child_frames[0].frame_id(), "CHILD");
+    }
+
+    #[tokio::test]
+    async fn iframes_created_before_parents_are_attached_to_them() {
+        let browser = MockBrowser::start(|method, params, session_id| match (method, session_id) {
+            ("Page.getFrameTree", Some(session_id)) if session_id == session_for("CHILD") => {
+                Ok(frame_tree("CHILD", Some("PAGE")))
+            }
+            ("Page.getFrameTree", Some(session_id)) if session_id == session_for("GRANDCHILD") => {
+                Ok(frame_tree("GRANDCHILD", Some("CHILD")))
+            }
+            _ => default_reply(method, params, session_id),
+        })
+        .await;
+        let (_connection, target_manager) = browser.connect_with_targets().await;
+
+        browser.emit_target_created("GRANDCHILD", "iframe");
+        browser.emit_target_created("CHILD", "iframe");
+        eventually(|| async { browser.commands("Page.getFrameTree").len() == 2 }).await;
+        assert!(
+            target_manager
+                .get_target(&"CHILD".to_string())
+                .await
+                .is_none()
+        );
+        assert!(
+            target_manager
+                .get_frame_inner(&"CHILD".to_string())
+                .await
+                .is_none()
+        );
+
+        browser.emit_target_created("PAGE", "page");
+        eventually(|| async {
+            target_manager
+                .get_frame_inner(&"GRANDCHILD".to_string())
+                .await
+                .is_some()
+        })
+        .await;
+
+        let page = target_manager
+            .get_target(&"PAGE".to_string())
+            .await
+            .unwrap();
+        for (frame_id, parent_id) in [("CHILD", "PAGE"), ("GRANDCHILD", "CHILD")] {
+            let target = target_manager
+                .get_target(&frame_id.to_string())
+                .await
+                .unwrap();
+            assert!(std::sync::Arc::ptr_eq(
+                &page.network_manager().unwrap(),
+                &target.network_manager().unwrap()
+            ));
+
+            let frame_inner = target_manager
+                .get_frame_inner(&frame_id.to_string())
+                .await
+                .unwrap();
+            assert_eq!(*frame_inner.target().await.target_id(), frame_id);
+            let parent_frame = frame_inner.parent_frame().await.unwrap();
+            assert_eq!(*parent_frame.frame_id(), parent_id);
+            let siblings = parent_frame.child_frames().await.unwrap();
+            assert_eq!(siblings.len(), 1);
+            assert_eq!(*siblings[0].frame_id(), frame_id);
+        }
+    }
+
+    #[tokio::test]
+    async fn streams_target_lifecycle_after_attaching_pages() {
+        let browser = MockBrowser::start(default_reply).await;
+        let (_connection, target_manager) = browser.connect_with_targets().await;
+        let mut targets = target_manager.subscribe_to_targets();
+
+        browser.emit_target_created("WORKER", "service_worker");
+        browser.emit_target_created("PAGE", "page");
+        browser.emit(
+            "Target.targetCrashed",
+            json!({ "targetId": "PAGE", "status": "crashed", "errorCode": "11" }),
+            None,
+        );
+        browser.emit(
+            "Target.targetDestroyed",
+            json!({ "targetId": "WORKER" }),
+            None,
+        );
+
+        match targets.recv().await.unwrap() {
+            TargetEvent::Created(target_info) => {
+                assert_eq!(target_info.target_id, "WORKER");
+                assert_eq!(target_info.target_type, "service_worker");
+            }
+            event => panic!("Unexpected event: {:?}", event),
+        }
+        match targets.recv().await.unwrap() {
+            TargetEvent::Created(target_info) => {
+                assert_eq!(target_info.target_id, "PAGE");
+                assert!(
+                    target_manager
+                        .get_page(&target_info.target_id)
+                        .await
+                        .is_some()
+                );
+            }
+            event => panic!("Unexpected event: {:?}", event),
+        }
+        match targets.recv().await.unwrap() {
+            TargetEvent::Crashed {
+                target_id, status, ..
+            } => {
+                assert_eq!(target_id, "PAGE");
+                assert_eq!(status, "crashed");
+            }
+            event => panic!("Unexpected event: {:?}", event),
+        }
+        match targets.recv().await.unwrap() {
+            TargetEvent::Destroyed(target_id) => assert_eq!(target_id, "WORKER"),
+            event => panic!("Unexpected event: {:?}", event),
+        }
+    }
+
+    #[tokio::test]
+    async fn attaches_workers_without_page_machinery() {
+        let browser = MockBrowser::start(|method, params, session_id| match method {
+            "Runtime.evaluate" => Ok(json!({ "result": { "type": "number", "value": 2 } })),
+            _ => default_reply(method, params, session_id),
+        })
+        .await;
+        let (_connection, target_manager) = browser.connect_with_targets().await;
+
+        browser.emit_target_created("SW", "service_worker");
+        eventually(|| async { !target_manager.get_workers().await.is_empty() }).await;
+
+        let worker = target_manager.get_workers().await.pop().unwrap();
+        assert_eq!(worker.worker_type(), "service_worker");
+        assert!(target_manager.get_targets().await.is_empty());
+        assert!(browser.commands("Page.enable").is_empty());
+
+        assert_eq!(worker.evaluate("1 + 1").await.unwrap(), json!(2));
+        let evaluated = browser.commands("Runtime.evaluate");
+        assert_eq!(evaluated[0].session_id.as_deref(), Some("session-SW"));
+
+        browser.emit("Target.targetDestroyed", json!({ "targetId": "SW" }), None);
+        eventually(|| async { target_manager.get_workers().await.is_empty() }).await;
+    }
+
+    #[tokio::test]
+    async fn closes_created_pages_when_last_browser_handle_drops() {
+        let browser = MockBrowser::start(default_reply).await;
+        let (connection, target_manager) = browser.connect_with_targets().await;
+        target_manager.created_pages.insert("PAGE".to_string());
+
+        let cdp_browser = Browser::new(connection.clone());
+        cdp_browser.close_on_drop(true);
+        let clone = cdp_browser.clone();
+        drop(cdp_browser);
+        assert!(browser.commands("Target.closeTarget").is_empty());
+
+        drop(clone);
+        eventually(|| async { !browser.commands("Target.closeTarget").is_empty() }).await;
+        assert_eq!(
+            browser.commands("Target.closeTarget")[0].params["targetId"],
+            "PAGE"
+        );
+        eventually(|| async {
+            connection
+                .send("Browser.getVersion", &json!({}), None)
+                .await
+                .is_err()
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn disposes_browser_context_with_its_pages_when_last_handle_drops() {
+        let browser = MockBrowser::start(|method, params, session_id| match method {
+            "Target.createBrowserContext" => Ok(json!({ "browserContextId": "CTX" })),
+            _ => default_reply(method, params, session_id),
+        })
+        .await;
+        let (_connection, target_manager) = browser.connect_with_targets().await;
+        browser.emit_target_created("PAGE", "page");
+        browser.emit(
+            "Target.targetCreated",
+            json!({
+                "targetInfo": {
+                    "targetId": "PAGE-CTX",
+                    "type": "page",
+                    "title": "",
+                    "url": "about:blank",
+                    "attached": false,
+                    "browserContextId": "CTX",
+                }
+            }),
+            None,
+        );
+        eventually(|| async { target_manager.get_targets().await.len() == 2 }).await;
+
+        let browser_context = target_manager.create_incognito_context().await.unwrap();
+        //Contexts which aren't incognito live as long as the browser
+        let persistent = target_manager
+            .create_browser_context(None, None)
+            .await
+            .unwrap();
+        drop(persistent);
+
+        let browser_context = target_manager.create_incognito_context().await.unwrap();
+        let clone = (*browser_context).clone();
+        drop(browser_context);
+        tokio::task::yield_now().await;
+        assert!(browser.commands("Target.disposeBrowserContext").is_empty());
+
+        drop(clone);
+        eventually(|| async { !browser.commands("Target.disposeBrowserContext").is_empty() }).await;
+        let closed = browser.commands("Target.closeTarget");
+        assert_eq!(closed.len(), 1);
+        assert_eq!(closed[0].params["targetId"], "PAGE-CTX");
+        assert_eq!(
+            browser.commands("Target.disposeBrowserContext")[0].params["browserContextId"],
+            "CTX"
+        );
+    }
+
+    #[tokio::test]
+    async fn pages_of_a_browser_context_keep_it_alive() {
+        let browser = MockBrowser::start(|method, params, session_id| match method {
+            "Target.createBrowserContext" => Ok(json!({ "browserContextId": "CTX" })),
+            "Target.createTarget" => Ok(json!({ "targetId": "PAGE-CTX" })),
+            _ => default_reply(method, params, session_id),
+        })
+        .await;
+        let (_connection, target_manager) = browser.connect_with_targets().await;
+
+        let browser_context = target_manager.create_incognito_context().await.unwrap();
+        let context = browser_context.clone();
+        let new_page = tokio::spawn(async move { context.new_page().await });
+        eventually(|| async { !browser.commands("Target.createTarget").is_empty() }).await;
+        browser.emit(
+            "Target.targetCreated",
+            json!({
+                "targetInfo": {
+                    "targetId": "PAGE-CTX",
+                    "type": "page",
+                    "title": "",
+                    "url": "about:blank",
+                    "attached": false,
+                    "browserContextId": "CTX",
+                }
+            }),
+            None,
+        );
+        let page = new_page.await.unwrap().unwrap();
+
+        drop(browser_context);
+        tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+        assert!(browser.commands("Target.disposeBrowserContext").is_empty());
+
+        drop(page);
+        eventually(|| async { !browser.commands("Target.disposeBrowserContext").is_empty() }).await;
+    }
+}
@@ -0,0 +1,50 @@
+use serde::Deserialize;
+
+//Idempotent, it runs as an init script on every new document and once more in the current one.
+//Buffered observers pick up entries recorded before the script was installed.
+pub(crate) const WEB_VITALS_JS: &str = r#"(() => {
+    if (globalThis.__chromatica_web_vitals) return;
+    const vitals = { lcp: null, cls: 0, fid: null, inp: null, fcp: null };
+    globalThis.__chromatica_web_vitals = vitals;
+    const observe = (type, callback, options = {}) => {
+        try {
+            new PerformanceObserver((list) => list.getEntries().forEach(callback))
+                .observe({ type, buffered: true, ...options });
+        } catch (e) {}
+    };
+    observe('largest-contentful-paint', (entry) => {
+        vitals.lcp = entry.renderTime || entry.loadTime || entry.startTime;
+    });
+    observe('layout-shift', (entry) => {
+        if (!entry.hadRecentInput) vitals.cls += entry.value;
+    });
+    observe('first-input', (entry) => {
+        vitals.fid = entry.processingStart - entry.startTime;
+    });
+    observe('event', (entry) => {
+        if (entry.interactionId) vitals.inp = Math.max(vitals.inp ?? 0, entry.duration);
+    }, { durationThreshold: 16 });
+    observe('paint', (entry) => {
+        if (entry.name === 'first-contentful-paint') vitals.fcp = entry.startTime;
+    });
+})()"#;
+
+pub(crate) const READ_WEB_VITALS_JS: &str = "({ ...globalThis.__chromatica_web_vitals })";
+
+///Core Web Vitals of the current document, times are in milliseconds since navigation start.
+///A metric is `None` until the page has produced it, e.g. FID and INP need a user interaction.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct WebVitals {
+    #[serde(rename = "lcp")]
+    pub largest_contentful_paint: Option<f64>,
+    ///Sum of shifts without recent input, not split into session windows.
+    #[serde(rename = "cls", default)]
+    pub cumulative_layout_shift: f64,
+    #[serde(rename = "fid")]
+    pub first_input_delay: Option<f64>,
+    ///Longest interaction seen so far.
+    #[serde(rename = "inp")]
+    pub interaction_to_next_paint: Option<f64>,
+    #[serde(rename = "fcp")]
+    pub first_contentful_paint: Option<f64>,
+}
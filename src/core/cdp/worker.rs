@@ -0,0 +1,68 @@
+use super::connection::Connection;
+use super::domains::runtime::{Evaluate, EvaluateResponse};
+use super::domains::target::{SessionId, TargetId};
+use anyhow::{Result, anyhow};
+use serde_json::Value;
+use std::sync::{Arc, Weak};
+
+///Dedicated, shared or service worker target. Workers have no DOM, so it's just a session to evaluate in.
+#[derive(Debug, Clone)]
+pub struct Worker {
+    connection: Weak<Connection>,
+    target_id: Arc<TargetId>,
+    session_id: Arc<SessionId>,
+    worker_type: String,
+    url: String,
+}
+
+impl Worker {
+    pub fn new(
+        connection: Weak<Connection>,
+        target_id: Arc<TargetId>,
+        session_id: Arc<SessionId>,
+        worker_type: String,
+        url: String,
+    ) -> Self {
+        Self {
+            connection,
+            target_id,
+            session_id,
+            worker_type,
+            url,
+        }
+    }
+
+    pub fn target_id(&self) -> Arc<TargetId> {
+        self.target_id.clone()
+    }
+
+    ///`worker`, `shared_worker` or `service_worker`.
+    pub fn worker_type(&self) -> &str {
+        &self.worker_type
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub async fn evaluate(&self, expression: &str) -> Result<Value> {
+        let Some(conn) = self.connection.upgrade() else {
+            return Err(anyhow!("Connection is not available"));
+        };
+        let params = Evaluate::new(expression).return_by_value(true).build();
+        let response = conn
+            .send("Runtime.evaluate", &params, Some(&self.session_id))
+            .await?
+            .result_as::<EvaluateResponse>()?;
+        if let Some(exception_details) = response.exception_details {
+            return Err(anyhow!(
+                "Evaluation failed: {}",
+                exception_details.message()
+            ));
+        }
+        match response.result.and_then(|result| result.value) {
+            Some(value) => Ok(value),
+            None => Ok(Value::Null),
+        }
+    }
+}
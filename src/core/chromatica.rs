@@ -2,13 +2,17 @@ use anyhow::{Result, anyhow};
 use reqwest::Client;
 use serde::Deserialize;
 use std::ops::Deref;
-use std::sync::Arc;
+use std::sync::{Arc, Weak};
 use tokio::fs;
 use tokio::process::Child;
+use tokio::time::Duration;
 
 use super::browser::Browser;
 use super::cdp::browser::Browser as CdpBrowser;
-use super::cdp::connection::Connection as CdpConnection;
+use super::cdp::connection::{
+    Connection as CdpConnection, ConnectionOptions, EventChannelMode, ProtocolDirection,
+    ProtocolLogger, ReconnectPolicy,
+};
 
 #[derive(Debug, Default, Clone, PartialEq, Deserialize)]
 enum Protocol {
@@ -53,11 +57,119 @@ struct BrowserConfig {
     protocol: Protocol,
 }
 
+///Library wide settings in one place, passed to `Chromatica::with_options`.
+#[derive(Debug, Clone)]
+pub struct ChromaticaOptions {
+    protocol: Option<String>,
+    connection_options: ConnectionOptions,
+}
+
+impl ChromaticaOptions {
+    pub fn new() -> Self {
+        Self {
+            protocol: None,
+            connection_options: ConnectionOptions::default(),
+        }
+    }
+
+    ///`cdp` (default) or `bidi`.
+    pub fn protocol(mut self, protocol: &str) -> Self {
+        self.protocol = Some(protocol.to_string());
+        self
+    }
+
+    ///How long a single protocol command may wait for its response, 30 seconds by default.
+    pub fn request_timeout(mut self, request_timeout: Duration) -> Self {
+        self.connection_options = self.connection_options.request_timeout(request_timeout);
+        self
+    }
+
+    ///Initial timeout of `wait_for_*` methods for new pages, 30 seconds by default. Pages can still change it.
+    pub fn default_timeout(mut self, default_timeout: Duration) -> Self {
+        self.connection_options = self.connection_options.default_timeout(default_timeout);
+        self
+    }
+
+    ///Max size of a single WebSocket message, `None` removes the limit.
+    pub fn max_message_size(mut self, max_message_size: Option<usize>) -> Self {
+        self.connection_options = self.connection_options.max_message_size(max_message_size);
+        self
+    }
+
+    pub fn max_frame_size(mut self, max_frame_size: Option<usize>) -> Self {
+        self.connection_options = self.connection_options.max_frame_size(max_frame_size);
+        self
+    }
+
+    ///Interval of WebSocket pings, off by default. Useful for remote browsers behind proxies which drop idle connections.
+    pub fn keepalive_interval(mut self, keepalive_interval: Option<Duration>) -> Self {
+        self.connection_options = self
+            .connection_options
+            .keepalive_interval(keepalive_interval);
+        self
+    }
+
+    ///Buffering of console and page error streams, unbounded by default. Bounded modes cap memory
+    ///when such a stream is read slower than the page logs. Internal event handling stays unbounded.
+    pub fn event_channel_mode(mut self, event_channel_mode: EventChannelMode) -> Self {
+        self.connection_options = self
+            .connection_options
+            .event_channel_mode(event_channel_mode);
+        self
+    }
+
+    ///Gets every raw protocol message sent to and received from the browser.
+    pub fn protocol_logger<F>(mut self, logger: F) -> Self
+    where
+        F: Fn(ProtocolDirection, &str) + Send + Sync + 'static,
+    {
+        self.connection_options = self
+            .connection_options
+            .protocol_logger(Some(ProtocolLogger::new(logger)));
+        self
+    }
+
+    ///What to do when the connection to the browser drops, `ReconnectPolicy::Never` is the only one so far.
+    pub fn reconnect_policy(mut self, reconnect_policy: ReconnectPolicy) -> Self {
+        self.connection_options = self.connection_options.reconnect_policy(reconnect_policy);
+        self
+    }
+
+    pub fn build(self) -> Self {
+        self
+    }
+}
+
+impl Default for ChromaticaOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[derive(Debug)]
 pub struct Chromatica {
     protocol: Protocol,
     browser_config: Option<BrowserConfig>,
     child: Option<Child>,
+    connection_options: ConnectionOptions,
+    connections: Vec<Weak<CdpConnection>>,
+}
+
+impl Drop for Chromatica {
+    fn drop(&mut self) {
+        let connections: Vec<Arc<CdpConnection>> =
+            self.connections.iter().filter_map(Weak::upgrade).collect();
+        if connections.is_empty() {
+            return;
+        }
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            handle.spawn(async move {
+                for connection in connections {
+                    connection.disconnect().await;
+                }
+            });
+        }
+    }
 }
 
 impl Chromatica {
@@ -70,12 +182,51 @@ impl Chromatica {
             protocol,
             browser_config: None,
             child: None,
+            connection_options: ConnectionOptions::default(),
+            connections: Vec::new(),
         }
     }
+
+    pub fn with_options(options: ChromaticaOptions) -> Result<Self> {
+        let protocol = match options.protocol.as_deref() {
+            Some(p) => Protocol::new(p)?,
+            None => Protocol::CDP,
+        };
+        Ok(Self {
+            protocol,
+            browser_config: None,
+            child: None,
+            connection_options: options.connection_options,
+            connections: Vec::new(),
+        })
+    }
+
+    ///Max size of a single WebSocket message, `None` removes the limit. Large full page screenshots and PDFs need it raised.
+    #[deprecated(
+        note = "use `ChromaticaOptions::max_message_size` with `Chromatica::with_options`"
+    )]
+    pub fn max_message_size(mut self, max_message_size: Option<usize>) -> Self {
+        self.connection_options = self
+            .connection_options
+            .clone()
+            .max_message_size(max_message_size);
+        self
+    }
+
+    #[deprecated(note = "use `ChromaticaOptions::max_frame_size` with `Chromatica::with_options`")]
+    pub fn max_frame_size(mut self, max_frame_size: Option<usize>) -> Self {
+        self.connection_options = self
+            .connection_options
+            .clone()
+            .max_frame_size(max_frame_size);
+        self
+    }
+
+    ///Connections live as long as this `Chromatica`, dropping it disconnects every browser it connected to.
     pub async fn connect(&mut self, port: u16, protocol: Option<&str>) -> Result<Browser> {
         let protocol = match protocol {
             Some(p) => Protocol::new(p)?,
-            None => Protocol::CDP,
+            None => self.protocol.clone(),
         };
         let debug_ws_url = format!("http://127.0.0.1:{}/json/version", port);
 
@@ -93,9 +244,12 @@ impl Chromatica {
 
                 match protocol {
                     Protocol::CDP => {
-                        let conn = CdpConnection::connect(&ws_url).await?;
+                        let conn =
+                            CdpConnection::connect_with_options(&ws_url, &self.connection_options)
+                                .await?;
                         let target_manager = conn.target_manager().unwrap().clone();
                         target_manager.init().await?;
+                        self.connections.push(Arc::downgrade(&conn));
                         let browser = CdpBrowser::new(conn);
                         Ok(Browser::CDP(browser))
                     }
@@ -1,7 +1,11 @@
+use futures::stream::{BoxStream, StreamExt};
 use std::collections::HashMap;
+use std::fmt;
 use tokio::time::Duration;
 
-use super::cdp::element::Element as CdpElement;
+use super::cdp::domains::page::PrintToPDF as CdpPrintToPDF;
+use super::cdp::element::{Element as CdpElement, VisibilityOptions};
+use super::cdp::page::ScreenshotOptions;
 
 use anyhow::Result;
 
@@ -30,6 +34,20 @@ impl Element {
         }
     }
 
+    ///Lazy `query_selector_all`, elements are resolved one by one as the stream is polled.
+    ///Queries with `>`, `,` or `text(` steps are still resolved upfront. A node that can't be resolved yields an error.
+    pub async fn query_selector_all_stream(
+        &self,
+        query: &str,
+    ) -> Result<BoxStream<'static, Result<Element>>> {
+        match self {
+            Self::CDP(element) => {
+                let stream = element.query_selector_all_stream(query).await?;
+                Ok(stream.map(|element| element.map(Element::CDP)).boxed())
+            } // Self::BiDi(element) => element.query_selector_all_stream(query).await,
+        }
+    }
+
     // pub async fn wait_for_selector(
     //     &self,
     //     query: &str,
@@ -64,6 +82,81 @@ impl Element {
         }
     }
 
+    pub async fn hover(&self) -> Result<()> {
+        match self {
+            Self::CDP(element) => element.hover().await,
+            // Self::BiDi(element) => element.hover().await,
+        }
+    }
+
+    ///Hovers the element and waits for `child_selector` to appear inside it.
+    ///Covers menus that only reveal their items on hover, no fixed sleep needed.
+    pub async fn hover_and_wait(
+        &self,
+        child_selector: &str,
+        timeout: Option<Duration>,
+    ) -> Result<Element> {
+        match self {
+            Self::CDP(element) => {
+                let result = element.hover_and_wait(child_selector, timeout).await?;
+                Ok(Element::CDP(result))
+            } // Self::BiDi(element) => element.hover_and_wait(child_selector, timeout).await,
+        }
+    }
+
+    pub async fn wait_for_stable(&self, timeout: Option<Duration>) -> Result<()> {
+        match self {
+            Self::CDP(element) => element.wait_for_stable(timeout).await,
+            // Self::BiDi(element) => element.wait_for_stable(timeout).await,
+        }
+    }
+
+    ///Checks the element against a CSS selector, e.g. to filter `query_selector_all` results by `.active`.
+    pub async fn matches(&self, selector: &str) -> Result<bool> {
+        match self {
+            Self::CDP(element) => element.matches(selector).await,
+            // Self::BiDi(element) => element.matches(selector).await,
+        }
+    }
+
+    ///Browser's own `checkVisibility()`, which unlike box model checks knows about `content-visibility`
+    ///and, with `VisibilityOptions::all`, about `opacity` and `visibility`. Text nodes use their parent element.
+    pub async fn check_visibility(&self, options: VisibilityOptions) -> Result<bool> {
+        match self {
+            Self::CDP(element) => element.check_visibility(options).await,
+            // Self::BiDi(element) => element.check_visibility(options).await,
+        }
+    }
+
+    ///Identity check for deduplicating results of overlapping queries, e.g. `find_by_text` and shadow traversal.
+    pub async fn drag_to(
+        &self,
+        target: &Element,
+        position: Option<(f32, f32)>,
+        html5_fallback: bool,
+    ) -> Result<()> {
+        match (self, target) {
+            (Self::CDP(element), Self::CDP(target)) => {
+                element.drag_to(target, position, html5_fallback).await
+            } // (Self::BiDi(element), Self::BiDi(target)) => element.drag_to(target, position, html5_fallback).await,
+        }
+    }
+
+    pub async fn is_same_node(&self, other: &Element) -> Result<bool> {
+        match (self, other) {
+            (Self::CDP(element), Self::CDP(other)) => element.is_same_node(other).await,
+            // (Self::BiDi(element), Self::BiDi(other)) => element.is_same_node(other).await,
+        }
+    }
+
+    ///Human readable label like `<button#submit.primary>`, also used by `Display` once fetched.
+    pub async fn describe(&self) -> Result<String> {
+        match self {
+            Self::CDP(element) => element.describe().await,
+            // Self::BiDi(element) => element.describe().await,
+        }
+    }
+
     pub async fn attributes(&self) -> Result<HashMap<String, String>> {
         match self {
             Self::CDP(element) => element.attributes().await,
@@ -78,6 +171,51 @@ impl Element {
         }
     }
 
+    ///`innerText` of the element, i.e. the text as rendered, unlike `text` which is the first text node.
+    pub async fn inner_text(&self) -> Result<String> {
+        match self {
+            Self::CDP(element) => element.inner_text().await,
+            // Self::BiDi(element) => element.inner_text().await,
+        }
+    }
+
+    ///Waits until `innerText` satisfies `predicate` and returns it, e.g. a status going from "Processing…" to "Done".
+    ///Works with substrings and regexes alike: `|text| re.is_match(text)`.
+    pub async fn wait_for_text<F>(&self, predicate: F, timeout: Option<Duration>) -> Result<String>
+    where
+        F: Fn(&str) -> bool + Send + 'static,
+    {
+        match self {
+            Self::CDP(element) => element.wait_for_text(predicate, timeout).await,
+            // Self::BiDi(element) => element.wait_for_text(predicate, timeout).await,
+        }
+    }
+
+    ///Current `value` property of an input, textarea or select.
+    pub async fn value(&self) -> Result<String> {
+        match self {
+            Self::CDP(element) => element.value().await,
+            // Self::BiDi(element) => element.value().await,
+        }
+    }
+
+    ///Sets `value` and dispatches `input` and `change` without simulating keystrokes, unlike `type_text`.
+    pub async fn set_value(&self, value: &str) -> Result<()> {
+        match self {
+            Self::CDP(element) => element.set_value(value).await,
+            // Self::BiDi(element) => element.set_value(value).await,
+        }
+    }
+
+    ///Focuses the element and empties it, dispatching `input` (and `change` for form controls).
+    ///Works for inputs, textareas and contenteditable elements.
+    pub async fn clear(&self) -> Result<()> {
+        match self {
+            Self::CDP(element) => element.clear().await,
+            // Self::BiDi(element) => element.clear().await,
+        }
+    }
+
     pub async fn type_text(&self, text: &str, delay: Option<u64>) -> Result<()> {
         match self {
             Self::CDP(element) => element.type_text(text, delay).await,
@@ -111,4 +249,31 @@ impl Element {
             } // Self::BiDi(element) => element.screenshot(save_path, format, quality, full_page).await,
         }
     }
+
+    pub async fn screenshot_with(&self, options: ScreenshotOptions<'_>) -> Result<String> {
+        match self {
+            Self::CDP(element) => element.screenshot_with(options).await,
+            // Self::BiDi(element) => element.screenshot_with(options).await,
+        }
+    }
+
+    pub async fn print_to_pdf<'a>(
+        &self,
+        save_path: Option<&str>,
+        options: Option<CdpPrintToPDF<'a>>,
+    ) -> Result<String> {
+        match self {
+            Self::CDP(element) => element.print_to_pdf(save_path, options).await,
+            // Self::BiDi(element) => element.print_to_pdf(save_path, options).await,
+        }
+    }
+}
+
+impl fmt::Display for Element {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CDP(element) => fmt::Display::fmt(element, f),
+            // Self::BiDi(element) => fmt::Display::fmt(element, f),
+        }
+    }
 }
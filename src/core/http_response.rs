@@ -1,6 +1,6 @@
 use super::cdp::domains::fetch::ResponseBody as CdpResponseBody;
 use super::cdp::domains::network::ResourceType as CdpResourceType;
-use super::cdp::http_response::HttpResponse as CdpHttpResponse;
+use super::cdp::http_response::{HttpResponse as CdpHttpResponse, RedirectHop};
 use anyhow::Result;
 use serde_json::Value;
 use std::collections::HashMap;
@@ -95,4 +95,18 @@ impl HttpResponse {
             // Self::BiDi(response) => response.url(),
         }
     }
+
+    pub fn is_redirect(&self) -> bool {
+        match self {
+            Self::CDP(response) => response.is_redirect(),
+            // Self::BiDi(response) => response.is_redirect(),
+        }
+    }
+
+    pub fn redirect_chain(&self) -> &[RedirectHop] {
+        match self {
+            Self::CDP(response) => response.redirect_chain(),
+            // Self::BiDi(response) => response.redirect_chain(),
+        }
+    }
 }
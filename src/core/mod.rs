@@ -8,6 +8,24 @@ pub mod http_response;
 pub mod iframe;
 pub mod js_dialogs;
 pub mod page;
+pub mod worker;
+pub use cdp::connection::{CdpError, EventChannelMode, ProtocolDirection, ReconnectPolicy};
+pub use cdp::console::{ConsoleMessage, PageError};
+pub use cdp::domains::browser::DownloadBehavior;
 pub use cdp::domains::emulation::{UserAgentBrandVersion, UserAgentMetadata};
-pub use cdp::domains::page::PrintToPDF;
-pub use cdp::emulation_manager::UserAgentOverride;
+pub use cdp::domains::network::ResourceKind;
+pub use cdp::domains::page::{Margins, PaperSize, PrintToPDF};
+pub use cdp::domains::target::TargetInfo;
+pub use cdp::domains::web_authn::{
+    AuthenticatorProtocol, AuthenticatorTransport, Credential, VirtualAuthenticatorOptions,
+};
+pub use cdp::element::VisibilityOptions;
+pub use cdp::emulation_manager::{
+    Country, DeviceMetrics, Geolocation, MediaEmulationGuard, UserAgentOverride,
+};
+pub use cdp::http_request::FailedRequest;
+pub use cdp::http_response::RedirectHop;
+pub use cdp::input_recorder::{InputRecording, RecordedInput};
+pub use cdp::page::ScreenshotOptions;
+pub use cdp::target_manager::TargetEvent;
+pub use cdp::web_vitals::WebVitals;
@@ -1,13 +1,26 @@
+use super::cdp::console::{ConsoleStream, PageErrorStream};
+use super::cdp::domains::browser::DownloadBehavior;
 use super::cdp::domains::page::{PrintToPDF as CdpPrintToPDF, ScriptIdentifier};
+use super::cdp::domains::web_authn::{AuthenticatorId, Credential, VirtualAuthenticatorOptions};
+use super::cdp::element::Element as CdpElement;
+use super::cdp::emulation_manager::DeviceMetrics as CdpDeviceMetrics;
+use super::cdp::emulation_manager::MediaEmulationGuard;
 use super::cdp::emulation_manager::UserAgentOverride as CdpUserAgentOverride;
+use super::cdp::emulation_manager::{Country, Geolocation};
+use super::cdp::http_request::FailedRequest;
 use super::cdp::http_response::HttpResponse as CdpHttpResponse;
+use super::cdp::input_recorder::InputRecording;
 use super::cdp::js_dialogs::JsDialog;
 use super::cdp::network_manager::{RequestStream, ResponseStream};
-use super::cdp::page::Page as CdpPage;
+use super::cdp::page::{Page as CdpPage, ScreenshotOptions};
+use super::cdp::web_vitals::WebVitals;
 use super::element::Element;
 use super::http_response::HttpResponse;
 
 use anyhow::Result;
+use futures::stream::{BoxStream, StreamExt};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
 use std::collections::HashMap;
 use tokio::sync::broadcast;
 use tokio::time::Duration;
@@ -47,6 +60,46 @@ impl Page {
         }
     }
 
+    ///CDP session id of the page, for correlating `send_cdp` calls with external tooling or DevTools traces.
+    pub async fn session_id(&self) -> Result<String> {
+        match self {
+            Self::CDP(page) => page.session_id().await,
+            // Self::BiDi(page) => page.session_id().await,
+        }
+    }
+
+    pub async fn target_id(&self) -> Result<String> {
+        match self {
+            Self::CDP(page) => page.target_id().await,
+            // Self::BiDi(page) => page.target_id().await,
+        }
+    }
+
+    ///Evaluates `expression` and returns the result by value, promises are awaited.
+    ///`timeout` bounds the script itself, an endless loop is terminated instead of holding the session until the request timeout.
+    ///None leaves it to the connection's request timeout.
+    pub async fn evaluate(&self, expression: &str, timeout: Option<Duration>) -> Result<Value> {
+        match self {
+            Self::CDP(page) => page.evaluate(expression, timeout).await,
+            // Self::BiDi(page) => page.evaluate(expression, timeout).await,
+        }
+    }
+
+    ///Evaluates `expression` and deserializes the result, e.g. `evaluate_as::<Vec<Product>>(...)` for scraped rows.
+    pub async fn evaluate_as<T: DeserializeOwned>(&self, expression: &str) -> Result<T> {
+        match self {
+            Self::CDP(page) => page.evaluate_as(expression).await,
+            // Self::BiDi(page) => page.evaluate_as(expression).await,
+        }
+    }
+
+    pub async fn send_cdp(&self, method: &str, params: Value) -> Result<Value> {
+        match self {
+            Self::CDP(page) => page.send_cdp(method, params).await,
+            // Self::BiDi(page) => page.send_cdp(method, params).await,
+        }
+    }
+
     pub async fn navigate(
         &self,
         url: &str,
@@ -59,6 +112,15 @@ impl Page {
         }
     }
 
+    ///Replaces the document with `html`. The document stays at its current url (`about:blank` for a new page),
+    ///pass `base_url` to resolve relative images and stylesheets against it.
+    pub async fn set_content(&self, html: &str, base_url: Option<&str>) -> Result<()> {
+        match self {
+            Self::CDP(page) => page.set_content(html, base_url).await,
+            // Self::BiDi(page) => page.set_content(html, base_url).await,
+        }
+    }
+
     pub async fn reload(&self, wait_until: Option<&str>, timeout: Option<Duration>) -> Result<()> {
         match self {
             Self::CDP(page) => page.reload(wait_until, timeout).await,
@@ -84,6 +146,30 @@ impl Page {
         }
     }
 
+    ///Like `close`, but returns only once the tab is actually gone, so a new page won't race its teardown.
+    pub async fn close_and_wait(self, timeout: Option<Duration>) -> Result<()> {
+        match self {
+            Self::CDP(page) => page.close_and_wait(timeout).await,
+            // Self::BiDi(page) => page.close_and_wait(timeout).await,
+        }
+    }
+
+    pub async fn scroll_gesture(
+        &self,
+        x: f64,
+        y: f64,
+        x_distance: f64,
+        y_distance: f64,
+        speed: Option<i32>,
+    ) -> Result<()> {
+        match self {
+            Self::CDP(page) => {
+                page.scroll_gesture(x, y, x_distance, y_distance, speed)
+                    .await
+            } // Self::BiDi(page) => page.scroll_gesture(x, y, x_distance, y_distance, speed).await,
+        }
+    }
+
     pub async fn set_credentials(&self, username: &str, password: &str) -> Result<()> {
         match self {
             Self::CDP(page) => page.set_credentials(username, password).await,
@@ -91,6 +177,139 @@ impl Page {
         }
     }
 
+    ///Window scroll offset as `(scrollX, scrollY)`.
+    pub async fn scroll_position(&self) -> Result<(f64, f64)> {
+        match self {
+            Self::CDP(page) => page.scroll_position().await,
+            // Self::BiDi(page) => page.scroll_position().await,
+        }
+    }
+
+    pub async fn scroll_to(&self, x: f64, y: f64) -> Result<()> {
+        match self {
+            Self::CDP(page) => page.scroll_to(x, y).await,
+            // Self::BiDi(page) => page.scroll_to(x, y).await,
+        }
+    }
+
+    ///Checks a media query against the page, e.g. `(prefers-color-scheme: dark)` after emulating it.
+    pub async fn matches_media(&self, query: &str) -> Result<bool> {
+        match self {
+            Self::CDP(page) => page.matches_media(query).await,
+            // Self::BiDi(page) => page.matches_media(query).await,
+        }
+    }
+
+    ///Allows or denies downloads for the page's browser context. `path` is where allowed downloads are saved.
+    pub async fn set_download_behavior(
+        &self,
+        behavior: DownloadBehavior,
+        path: Option<&str>,
+    ) -> Result<()> {
+        match self {
+            Self::CDP(page) => page.set_download_behavior(behavior, path).await,
+            // Self::BiDi(page) => page.set_download_behavior(behavior, path).await,
+        }
+    }
+
+    ///Overrides viewport size and device scale factor, screenshots are taken at that scale factor.
+    ///The override is kept for the page's iframes and survives navigations.
+    pub async fn set_viewport(&self, device_metrics: CdpDeviceMetrics) -> Result<()> {
+        match self {
+            Self::CDP(page) => page.set_viewport(device_metrics).await,
+            // Self::BiDi(page) => page.set_viewport(device_metrics).await,
+        }
+    }
+
+    ///Emulates CSS media type, `print` or `screen`. None resets it.
+    ///Like the viewport, the override persists across navigations until reset, see `with_media` for a scoped one.
+    pub async fn emulate_media(&self, media: Option<&str>) -> Result<()> {
+        match self {
+            Self::CDP(page) => page.emulate_media(media).await,
+            // Self::BiDi(page) => page.emulate_media(media).await,
+        }
+    }
+
+    pub async fn emulated_media(&self) -> Result<Option<String>> {
+        match self {
+            Self::CDP(page) => page.emulated_media().await,
+            // Self::BiDi(page) => page.emulated_media().await,
+        }
+    }
+
+    ///Turns script execution off or back on. Set it before navigating, scripts already loaded keep running.
+    pub async fn set_javascript_enabled(&self, enabled: bool) -> Result<()> {
+        match self {
+            Self::CDP(page) => page.set_javascript_enabled(enabled).await,
+            // Self::BiDi(page) => page.set_javascript_enabled(enabled).await,
+        }
+    }
+
+    pub async fn javascript_enabled(&self) -> Result<bool> {
+        match self {
+            Self::CDP(page) => page.javascript_enabled().await,
+            // Self::BiDi(page) => page.javascript_enabled().await,
+        }
+    }
+
+    ///None clears the override. The page still needs the geolocation permission to read it.
+    pub async fn set_geolocation(&self, geolocation: Option<Geolocation>) -> Result<()> {
+        match self {
+            Self::CDP(page) => page.set_geolocation(geolocation).await,
+            // Self::BiDi(page) => page.set_geolocation(geolocation).await,
+        }
+    }
+
+    ///IANA timezone id like `Europe/Berlin`, None restores the system timezone.
+    pub async fn set_timezone(&self, timezone: Option<&str>) -> Result<()> {
+        match self {
+            Self::CDP(page) => page.set_timezone(timezone).await,
+            // Self::BiDi(page) => page.set_timezone(timezone).await,
+        }
+    }
+
+    ///Locale used by `Intl` and date formatting, e.g. `de-DE`. None restores the system locale.
+    pub async fn set_locale(&self, locale: Option<&str>) -> Result<()> {
+        match self {
+            Self::CDP(page) => page.set_locale(locale).await,
+            // Self::BiDi(page) => page.set_locale(locale).await,
+        }
+    }
+
+    ///Capital's coordinates, timezone and default locale of `country` in one call, so geo-gated
+    ///content doesn't see e.g. US coordinates with a European timezone.
+    pub async fn emulate_country(&self, country: Country) -> Result<()> {
+        match self {
+            Self::CDP(page) => page.emulate_country(country).await,
+            // Self::BiDi(page) => page.emulate_country(country).await,
+        }
+    }
+
+    ///Emulates `media` until the returned guard is dropped, then restores the previous media.
+    ///Restoring on drop happens in the background, await `MediaEmulationGuard::restore` to be sure it's applied.
+    pub async fn with_media(&self, media: &str) -> Result<MediaEmulationGuard> {
+        match self {
+            Self::CDP(page) => page.with_media(media).await,
+            // Self::BiDi(page) => page.with_media(media).await,
+        }
+    }
+
+    ///Viewport override set with `set_viewport`, if any.
+    pub async fn viewport(&self) -> Result<Option<CdpDeviceMetrics>> {
+        match self {
+            Self::CDP(page) => page.viewport().await,
+            // Self::BiDi(page) => page.viewport().await,
+        }
+    }
+
+    ///`window.devicePixelRatio` as the page sees it, emulated or not.
+    pub async fn device_pixel_ratio(&self) -> Result<f64> {
+        match self {
+            Self::CDP(page) => page.device_pixel_ratio().await,
+            // Self::BiDi(page) => page.device_pixel_ratio().await,
+        }
+    }
+
     pub async fn set_user_agent(&self, user_agent: CdpUserAgentOverride) -> Result<()> {
         match self {
             Self::CDP(page) => page.set_user_agent(user_agent).await,
@@ -105,6 +324,7 @@ impl Page {
         }
     }
 
+    ///`quality` (0-100) is accepted only with `jpeg` or `webp` format, png is lossless.
     pub async fn screenshot(
         &self,
         save_path: Option<&str>,
@@ -117,6 +337,14 @@ impl Page {
         }
     }
 
+    ///`screenshot` with further settings, e.g. `ScreenshotOptions::hide_caret`.
+    pub async fn screenshot_with(&self, options: ScreenshotOptions<'_>) -> Result<String> {
+        match self {
+            Self::CDP(page) => page.screenshot_with(options).await,
+            // Self::BiDi(page) => page.screenshot_with(options).await,
+        }
+    }
+
     pub async fn print_to_pdf<'a>(
         &self,
         save_path: Option<&str>,
@@ -135,6 +363,62 @@ impl Page {
         }
     }
 
+    ///Resource loads which failed, including those of the page's iframes. Doesn't need request interception.
+    pub async fn subscribe_to_failed_requests(&self) -> Result<broadcast::Receiver<FailedRequest>> {
+        match self {
+            Self::CDP(page) => page.subscribe_to_failed_requests().await,
+            // Self::BiDi(page) => page.subscribe_to_failed_requests().await,
+        }
+    }
+
+    ///Starts capturing every mouse, keyboard, touch and scroll gesture command the page dispatches, with timestamps.
+    ///Inputs inside out-of-process iframes go through their own session and aren't captured.
+    pub async fn start_input_recording(&self) -> Result<()> {
+        match self {
+            Self::CDP(page) => page.start_input_recording().await,
+            // Self::BiDi(page) => page.start_input_recording().await,
+        }
+    }
+
+    pub async fn stop_input_recording(&self) -> Result<InputRecording> {
+        match self {
+            Self::CDP(page) => page.stop_input_recording().await,
+            // Self::BiDi(page) => page.stop_input_recording().await,
+        }
+    }
+
+    ///Re-dispatches a recording with its original timing, e.g. one loaded with `InputRecording::from_json`.
+    pub async fn replay_input(&self, recording: &InputRecording) -> Result<()> {
+        match self {
+            Self::CDP(page) => page.replay_input(recording).await,
+            // Self::BiDi(page) => page.replay_input(recording).await,
+        }
+    }
+
+    ///Requests of the page and its iframes which are still in flight, handy for custom network idle waits.
+    pub async fn pending_request_count(&self) -> Result<usize> {
+        match self {
+            Self::CDP(page) => page.pending_request_count().await,
+            // Self::BiDi(page) => page.pending_request_count().await,
+        }
+    }
+
+    ///Console messages of the page. Runtime domain is enabled while the stream is alive.
+    pub async fn subscribe_to_console(&self) -> Result<ConsoleStream> {
+        match self {
+            Self::CDP(page) => page.subscribe_to_console().await,
+            // Self::BiDi(page) => page.subscribe_to_console().await,
+        }
+    }
+
+    ///Uncaught exceptions thrown in the page.
+    pub async fn subscribe_to_page_errors(&self) -> Result<PageErrorStream> {
+        match self {
+            Self::CDP(page) => page.subscribe_to_page_errors().await,
+            // Self::BiDi(page) => page.subscribe_to_page_errors().await,
+        }
+    }
+
     pub async fn subscribe_to_js_dialogs(&self) -> Result<broadcast::Receiver<JsDialog>> {
         match self {
             Self::CDP(page) => page.subscribe_to_js_dialogs().await,
@@ -160,6 +444,39 @@ impl Page {
         }
     }
 
+    ///Runs `action` and returns the first response matching `predicate`, e.g. a click that submits a form.
+    pub async fn expect_response<F, Fut, T>(
+        &self,
+        predicate: F,
+        action: Fut,
+        timeout: Option<Duration>,
+    ) -> Result<HttpResponse>
+    where
+        F: Fn(&HttpResponse) -> bool + Send + 'static,
+        Fut: Future<Output = Result<T>>,
+    {
+        match self {
+            Self::CDP(page) => {
+                let adapted_predicate =
+                    move |resp: &CdpHttpResponse| predicate(&HttpResponse::CDP(resp.clone()));
+                let response = page
+                    .expect_response(adapted_predicate, action, timeout)
+                    .await?;
+                Ok(HttpResponse::CDP(response))
+            }
+        }
+    }
+
+    ///Imports a Netscape cookie file (cookies.txt, as exported by curl or browser extensions) or a `Cookie:` header string.
+    ///Header cookies are set for the current page url.
+    pub async fn import_cookies(&self, jar: &str) -> Result<()> {
+        match self {
+            Self::CDP(page) => page.import_cookies(jar).await,
+            // Self::BiDi(page) => page.import_cookies(jar).await,
+        }
+    }
+
+    ///Replaces the whole set of extra headers, use `add_extra_header` to change a single one.
     pub async fn set_extra_headers(&self, headers: HashMap<&str, &str>) -> Result<()> {
         match self {
             Self::CDP(page) => page.set_extra_headers(headers).await,
@@ -167,6 +484,21 @@ impl Page {
         }
     }
 
+    ///Sets one extra header and keeps the others, e.g. to rotate an auth token next to static headers.
+    pub async fn add_extra_header(&self, name: &str, value: &str) -> Result<()> {
+        match self {
+            Self::CDP(page) => page.add_extra_header(name, value).await,
+            // Self::BiDi(page) => page.add_extra_header(name, value).await,
+        }
+    }
+
+    pub async fn remove_extra_header(&self, name: &str) -> Result<()> {
+        match self {
+            Self::CDP(page) => page.remove_extra_header(name).await,
+            // Self::BiDi(page) => page.remove_extra_header(name).await,
+        }
+    }
+
     pub async fn clear_extra_headers(&self) -> Result<()> {
         match self {
             Self::CDP(page) => page.clear_extra_headers().await,
@@ -206,6 +538,20 @@ impl Page {
         }
     }
 
+    ///Lazy `query_selector_all` for large result sets, elements are resolved one by one as the stream is polled.
+    ///Queries with `>`, `,` or `text(` steps are still resolved upfront. A node that can't be resolved yields an error.
+    pub async fn query_selector_all_stream(
+        &self,
+        query: &str,
+    ) -> Result<BoxStream<'static, Result<Element>>> {
+        match self {
+            Self::CDP(page) => {
+                let stream = page.query_selector_all_stream(query).await?;
+                Ok(stream.map(|element| element.map(Element::CDP)).boxed())
+            } // Self::BiDi(page) => page.query_selector_all_stream(query).await,
+        }
+    }
+
     // pub async fn wait_for_selector(
     //     &self,
     //     query: &str,
@@ -236,6 +582,62 @@ impl Page {
         }
     }
 
+    ///Races the selectors, e.g. a dashboard or an error banner after login. Returns the index of the one that matched.
+    pub async fn wait_for_any_selector(
+        &self,
+        queries: &[&str],
+        timeout: Option<Duration>,
+    ) -> Result<(usize, Element)> {
+        match self {
+            Self::CDP(page) => {
+                let (index, cdp_element) = page.wait_for_any_selector(queries, timeout).await?;
+                Ok((index, Element::CDP(cdp_element)))
+            } // Self::BiDi(page) => page.wait_for_any_selector(queries, timeout).await,
+        }
+    }
+
+    ///Reads attributes of many elements with far fewer round trips than calling `attributes` on each.
+    ///Results are in the same order as `elements`.
+    pub async fn get_attributes_for(
+        &self,
+        elements: &[Element],
+    ) -> Result<Vec<HashMap<String, String>>> {
+        match self {
+            Self::CDP(page) => {
+                let elements: Vec<CdpElement> = elements
+                    .iter()
+                    .map(|element| match element {
+                        Element::CDP(element) => element.clone(),
+                    })
+                    .collect();
+                page.get_attributes_for(&elements).await
+            } // Self::BiDi(page) => page.get_attributes_for(elements).await,
+        }
+    }
+
+    ///Waits until `function` called with `args` returns a truthy value.
+    ///If that value is a DOM node, it's returned as an element.
+    pub async fn wait_for_function(
+        &self,
+        function: &str,
+        args: Vec<Value>,
+        timeout: Option<Duration>,
+    ) -> Result<Option<Element>> {
+        match self {
+            Self::CDP(page) => {
+                let cdp_element = page.wait_for_function(function, args, timeout).await?;
+                Ok(cdp_element.map(Element::CDP))
+            } // Self::BiDi(page) => page.wait_for_function(function, args, timeout).await,
+        }
+    }
+
+    pub async fn web_vitals(&self) -> Result<WebVitals> {
+        match self {
+            Self::CDP(page) => page.web_vitals().await,
+            // Self::BiDi(page) => page.web_vitals().await,
+        }
+    }
+
     pub async fn add_evaluate_on_new_document(&self, script: &str) -> Result<ScriptIdentifier> {
         match self {
             Self::CDP(page) => page.add_evaluate_on_new_document(script).await,
@@ -243,6 +645,28 @@ impl Page {
         }
     }
 
+    ///Runs `script` on every new document in the isolated world `world_name`, hidden from page scripts.
+    pub async fn add_evaluate_on_new_document_in_world(
+        &self,
+        script: &str,
+        world_name: &str,
+    ) -> Result<ScriptIdentifier> {
+        match self {
+            Self::CDP(page) => {
+                page.add_evaluate_on_new_document_in_world(script, world_name)
+                    .await
+            } // Self::BiDi(page) => page.add_evaluate_on_new_document_in_world(script, world_name).await,
+        }
+    }
+
+    ///Evaluates in the isolated world `world_name`, where init scripts registered for that world live.
+    pub async fn evaluate_in_world(&self, world_name: &str, expression: &str) -> Result<Value> {
+        match self {
+            Self::CDP(page) => page.evaluate_in_world(world_name, expression).await,
+            // Self::BiDi(page) => page.evaluate_in_world(world_name, expression).await,
+        }
+    }
+
     pub async fn remove_evaluate_on_new_document(
         &self,
         script_identifier: &ScriptIdentifier,
@@ -255,6 +679,47 @@ impl Page {
         }
     }
 
+    pub async fn add_virtual_authenticator(
+        &self,
+        options: VirtualAuthenticatorOptions,
+    ) -> Result<AuthenticatorId> {
+        match self {
+            Self::CDP(page) => page.add_virtual_authenticator(options).await,
+            // Self::BiDi(page) => page.add_virtual_authenticator(options).await,
+        }
+    }
+
+    pub async fn remove_virtual_authenticator(
+        &self,
+        authenticator_id: &AuthenticatorId,
+    ) -> Result<()> {
+        match self {
+            Self::CDP(page) => page.remove_virtual_authenticator(authenticator_id).await,
+            // Self::BiDi(page) => page.remove_virtual_authenticator(authenticator_id).await,
+        }
+    }
+
+    pub async fn add_credential(
+        &self,
+        authenticator_id: &AuthenticatorId,
+        credential: Credential,
+    ) -> Result<()> {
+        match self {
+            Self::CDP(page) => page.add_credential(authenticator_id, credential).await,
+            // Self::BiDi(page) => page.add_credential(authenticator_id, credential).await,
+        }
+    }
+
+    pub async fn get_credentials(
+        &self,
+        authenticator_id: &AuthenticatorId,
+    ) -> Result<Vec<Credential>> {
+        match self {
+            Self::CDP(page) => page.get_credentials(authenticator_id).await,
+            // Self::BiDi(page) => page.get_credentials(authenticator_id).await,
+        }
+    }
+
     // pub async fn wait_for_dom_storage_item_added(
     //     &self,
     //     key: &str,
@@ -0,0 +1,43 @@
+use anyhow::Result;
+use serde_json::Value;
+
+use super::cdp::worker::Worker as CdpWorker;
+
+#[derive(Debug, Clone)]
+pub enum Worker {
+    CDP(CdpWorker),
+    // BiDi(BiDiWorker),
+}
+
+impl Worker {
+    pub fn target_id(&self) -> String {
+        match self {
+            Self::CDP(worker) => worker.target_id().to_string(),
+            // Self::BiDi(worker) => worker.target_id(),
+        }
+    }
+
+    ///`worker`, `shared_worker` or `service_worker`.
+    pub fn worker_type(&self) -> &str {
+        match self {
+            Self::CDP(worker) => worker.worker_type(),
+            // Self::BiDi(worker) => worker.worker_type(),
+        }
+    }
+
+    ///Script url the worker was started with.
+    pub fn url(&self) -> &str {
+        match self {
+            Self::CDP(worker) => worker.url(),
+            // Self::BiDi(worker) => worker.url(),
+        }
+    }
+
+    ///Evaluates `expression` in the worker's global scope, promises are awaited and the result is returned by value.
+    pub async fn evaluate(&self, expression: &str) -> Result<Value> {
+        match self {
+            Self::CDP(worker) => worker.evaluate(expression).await,
+            // Self::BiDi(worker) => worker.evaluate(expression).await,
+        }
+    }
+}
@@ -1,6 +1,14 @@
 mod core;
 pub use core::browser::Browser;
-pub use core::chromatica::Chromatica;
+pub use core::chromatica::{Chromatica, ChromaticaOptions};
 pub use core::element::Element;
 pub use core::page::Page;
-pub use core::{PrintToPDF, UserAgentBrandVersion, UserAgentMetadata, UserAgentOverride};
+pub use core::worker::Worker;
+pub use core::{
+    AuthenticatorProtocol, AuthenticatorTransport, CdpError, ConsoleMessage, Country, Credential,
+    DeviceMetrics, DownloadBehavior, EventChannelMode, FailedRequest, Geolocation, InputRecording,
+    Margins, MediaEmulationGuard, PageError, PaperSize, PrintToPDF, ProtocolDirection,
+    ReconnectPolicy, RecordedInput, RedirectHop, ResourceKind, ScreenshotOptions, TargetEvent,
+    TargetInfo, UserAgentBrandVersion, UserAgentMetadata, UserAgentOverride,
+    VirtualAuthenticatorOptions, VisibilityOptions, WebVitals,
+};